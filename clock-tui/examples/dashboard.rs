@@ -0,0 +1,71 @@
+//! A tclock timer and clock embedded side by side in another ratatui app.
+//! Press <SPACE> to pause the timer and q to quit.
+
+use std::error::Error;
+use std::io;
+use std::time::Duration;
+
+use clock_tui::widgets::{ClockWidget, ColorSpec, FontKind, Pause, TimerState, TimerWidget};
+use crossterm::event::{self, Event, KeyCode};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::ExecutableCommand;
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Style};
+use ratatui::widgets::{Block, Borders};
+use ratatui::Terminal;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let timer = TimerWidget::builder()
+        .durations(vec![
+            chrono::Duration::minutes(25),
+            chrono::Duration::minutes(5),
+        ])
+        .titles(vec!["Work".to_string(), "Break".to_string()])
+        .font(FontKind::HalfBlock)
+        .style(Style::default().fg(Color::Yellow))
+        .show_progress(true)
+        .build();
+    let mut timer_state = TimerState::default();
+    let clock = ClockWidget::builder()
+        .font(FontKind::Braille)
+        .color(ColorSpec::Gradient(vec![Color::Cyan, Color::Magenta]))
+        .show_date(false)
+        .build();
+
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    stdout.execute(EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(&mut stdout))?;
+
+    loop {
+        terminal.draw(|f| {
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .split(f.size());
+            let timer_block = Block::default().borders(Borders::ALL).title("Pomodoro");
+            let clock_block = Block::default().borders(Borders::ALL).title("Now");
+            f.render_stateful_widget(&timer, timer_block.inner(chunks[0]), &mut timer_state);
+            f.render_widget(timer_block, chunks[0]);
+            f.render_widget(&clock, clock_block.inner(chunks[1]));
+            f.render_widget(clock_block, chunks[1]);
+        })?;
+
+        if event::poll(Duration::from_millis(250))? {
+            if let Event::Key(key) = event::read()? {
+                match key.code {
+                    KeyCode::Char(' ') => timer_state.toggle_paused(),
+                    KeyCode::Char('q') | KeyCode::Esc => break,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    drop(terminal);
+    disable_raw_mode()?;
+    stdout.execute(LeaveAlternateScreen)?;
+    Ok(())
+}
@@ -1,3 +1,7 @@
+use std::net::SocketAddr;
+use std::path::Path;
+use std::rc::Rc;
+
 use chrono::DateTime;
 use chrono::Duration;
 use chrono::Local;
@@ -9,19 +13,31 @@ use chrono_tz::Tz;
 use clap::Subcommand;
 use crossterm::event::KeyCode;
 use ratatui::{
+    layout::Rect,
     style::{Color, Style},
+    widgets::{Clear, Paragraph},
     Frame,
 };
 use regex::Regex;
+use serde::Serialize;
 
-use self::modes::Clock;
-use self::modes::Countdown;
-use self::modes::DurationFormat;
-use self::modes::Pause;
-use self::modes::Stopwatch;
-use self::modes::Timer;
+use self::action::{Action, Request};
+use self::bar::BarFormat;
+use self::http::HttpServer;
+use self::modes::{format_duration, DurationFormat, Look, Pause, Threshold, MAX_DURATION};
+use self::modes::{parse_end_time_format, DEFAULT_END_TIME_FORMAT};
+use self::modes::{ClockWidget, CountdownWidget, StopwatchWidget, TimerWidget};
+use self::modes::{Status, DEFAULT_STATUS_TEMPLATE};
+use self::modes::{StopwatchState, TimerState};
+use self::theme::Theme;
 
+pub mod action;
+pub mod bar;
+#[cfg(unix)]
+pub mod control;
+pub mod http;
 pub mod modes;
+pub mod theme;
 
 #[derive(Debug, Subcommand)]
 pub enum Mode {
@@ -70,6 +86,27 @@ pub enum Mode {
         /// Command to run when the timer ends
         #[clap(long, short, num_args = 1.., allow_hyphen_values = true)]
         execute: Vec<String>,
+
+        /// Show a progress bar for the current duration
+        #[clap(long, action)]
+        progress: bool,
+
+        /// Show a progress bar for the whole sequence of durations
+        #[clap(long, action)]
+        total_progress: bool,
+
+        /// Show the list of durations with their titles and the time each one ends
+        #[clap(long, action)]
+        segments: bool,
+
+        /// Show when the current duration and all durations end
+        #[clap(long, action)]
+        end_time: bool,
+
+        /// Format of the end time, implies --end-time. {segment} and {all} are replaced
+        /// by the end times, formatted as %H:%M unless given a format like {all:%I:%M %p}
+        #[clap(long, value_parser = parse_end_time_format)]
+        end_time_format: Option<String>,
     },
     /// The stopwatch mode displays the elapsed time since it was started.
     Stopwatch,
@@ -77,7 +114,7 @@ pub enum Mode {
     Countdown {
         /// The target time to countdown to, eg. "2023-01-01", "20:00", "2022-12-25 20:00:00" or "2022-12-25T20:00:00-04:00"
         #[clap(long, short, value_parser = parse_datetime)]
-        time: DateTime<Local>,
+        time: TimeSpec,
 
         /// Title or description for countdown show in header
         #[clap(long, short = 'T')]
@@ -94,45 +131,209 @@ pub enum Mode {
         /// Show milliseconds
         #[clap(short, long, action)]
         millis: bool,
+
+        /// Show a progress bar of the time passed since the countdown started
+        #[clap(long, action)]
+        progress: bool,
+
+        /// Start time the progress bar is measured from instead, same formats as --time, implies --progress
+        #[clap(long, value_parser = parse_datetime)]
+        from: Option<TimeSpec>,
+    },
+}
+
+/// The subcommands, a mode to run or `ctl` to drive a tclock running already.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    #[clap(flatten)]
+    Mode(Mode),
+    /// Send a command to a tclock started with --control and print its reply.
+    Ctl {
+        /// Name given to --control
+        #[clap(value_parser = parse_name)]
+        name: String,
+        /// One of: pause, resume, toggle, add <DURATION> (eg. add 5m), skip, lap, reset,
+        /// or status to print the status as JSON
+        #[clap(required = true, num_args = 1..)]
+        command: Vec<String>,
     },
 }
 
-use crate::config::Config;
+use crate::clock_text::color::{parse_color_spec, ColorSpec, TextColors};
+use crate::clock_text::font::effect::Effect;
+use crate::clock_text::font::figlet::FigletFont;
+use crate::clock_text::font::FontKind;
+use crate::clock_text::transition::Transition;
+use crate::config::{self, BricksFontConfig, ColorsConfig, Config, ThresholdConfig};
+use crate::session::{SessionKind, SessionState};
+use crate::time_source::{SystemClock, TimeSource};
 
 #[derive(clap::Parser, Default)]
 #[clap(name = "tclock", about = "A clock app in terminal", long_about = None)]
 pub struct App {
     #[clap(subcommand)]
-    pub mode: Option<Mode>,
+    pub command: Option<Command>,
     /// Foreground color of the clock, possible values are:
     ///     a) Any one of: Black, Red, Green, Yellow, Blue, Magenta, Cyan, Gray, DarkGray, LightRed, LightGreen, LightYellow, LightBlue, LightMagenta, LightCyan, White.
-    ///     b) Hexadecimal color code: #RRGGBB.
-    #[clap(short, long, value_parser = parse_color)]
-    pub color: Option<Color>,
-    /// Size of the clock, should be a positive integer (>=1).
-    #[clap(short, long, value_parser)]
-    pub size: Option<u16>,
+    ///     b) X11/CSS color name, eg. tomato or "dark slate gray".
+    ///     c) Color code: #RGB, #RRGGBB, rgb(R, G, B) or hsl(H, S%, L%).
+    ///     d) Index in the 256 color palette: 0-255.
+    ///     e) Gradient across the clock through two or more colors, eg. #ff0000..#0000ff or red..yellow..green.
+    ///     f) rainbow: a slowly cycling rainbow.
+    #[clap(short, long, value_parser = parse_color_spec)]
+    pub color: Option<ColorSpec>,
+    /// Size of the clock, a number of at least 1.
+    /// Fractional sizes are honored by the braille and half-block fonts, bricks fonts round to an integer.
+    #[clap(short, long, value_parser = parse_size)]
+    pub size: Option<f32>,
+    /// Font of the clock, possible values are:
+    ///     a) bricks: full blocks, the default.
+    ///     b) half-block: half blocks, square pixels and half the height of bricks.
+    ///     c) braille: thin outlines drawn with braille dots, for small panes.
+    ///     d) Path to a FIGlet font file, eg. path/to/font.flf.
+    ///     e) Path to a bricks font file, eg. path/to/font.toml.
+    ///     f) Name of a font in ~/.config/tclock/fonts/, eg. "standard" for standard.flf or standard.toml.
+    #[clap(short, long, value_parser = parse_font)]
+    pub font: Option<FontKind>,
+    /// Effect drawn on the clock digits, possible values are: shadow, outline, 3d.
+    #[clap(short, long, value_parser = parse_effect)]
+    pub effect: Option<Effect>,
+    /// Animate digits when they change, possible values are: flip, roll, dissolve.
+    #[clap(long, value_parser = parse_transition)]
+    pub transition: Option<Transition>,
+    /// Theme of the clock, possible values are:
+    ///     a) Any one of: default, dark, light, solarized, high-contrast.
+    ///     b) Path to a theme file, eg. path/to/theme.toml.
+    ///     c) Name of a theme in ~/.config/tclock/themes/, eg. "nord" for nord.toml.
+    #[clap(long, value_parser = Theme::load)]
+    pub theme: Option<Theme>,
+    /// Save the timer or stopwatch to ~/.local/state/tclock/<SESSION>.toml and restore
+    /// it when run again with the same session name, time passed while closed included.
+    #[clap(long, value_parser = parse_name)]
+    pub session: Option<String>,
+    /// Take commands like pause, add 5m or status on the socket
+    /// $XDG_RUNTIME_DIR/tclock/<NAME>.sock, eg. with `tclock ctl <NAME> pause`.
+    #[clap(long, value_name = "NAME", value_parser = parse_name)]
+    pub control: Option<String>,
+    /// Serve the state as JSON at GET /state, commands at POST /pause, /resume, /toggle,
+    /// /skip, /lap, /reset and /add (with a duration like 5m as body), and a page showing
+    /// the clock at /, eg. on 127.0.0.1:7878. Anyone who can reach the address can use it.
+    #[clap(long, value_name = "ADDR", value_parser = parse_socket_addr)]
+    pub http: Option<SocketAddr>,
+    /// Draw in this many rows under the shell prompt instead of the whole terminal,
+    /// and leave the last frame on the screen on exit.
+    #[clap(long, value_name = "ROWS", value_parser = clap::value_parser!(u16).range(1..))]
+    pub inline: Option<u16>,
+    /// Print the clock as one line of text whenever it changes instead of drawing it,
+    /// for status bars like tmux, polybar or i3bar.
+    #[clap(long, action)]
+    pub plain: bool,
+    /// Print the line in the i3bar protocol instead, clicks toggle pause (left),
+    /// reset (middle) and skip to the next duration of a timer (right).
+    #[clap(long, action, conflicts_with_all = ["plain", "waybar"])]
+    pub i3bar: bool,
+    /// Print the line as JSON of a waybar custom module instead, with the
+    /// tooltip, class (running, paused, warning or finished) and percentage.
+    #[clap(long, action, conflicts_with_all = ["plain"])]
+    pub waybar: bool,
+    /// Template of the line printed with --plain, implies --plain. {title}, {remaining},
+    /// {elapsed}, {time}, {date} and {state} are replaced by what the mode shows, times
+    /// in whole seconds unless written like {remaining:millis}.
+    /// Default: "{title} {remaining}{elapsed}{time}"
+    #[clap(long)]
+    pub template: Option<String>,
+    /// Pretend the clock starts at this time, to reproduce what is shown at a given time
+    #[clap(long, hide = true, value_parser = parse_now)]
+    pub now: Option<DateTime<Local>>,
 
+    /// The mode of `command`, or the default one once initialized
+    #[clap(skip)]
+    pub mode: Option<Mode>,
     #[clap(skip)]
-    clock: Option<Clock>,
+    time_source: Option<Rc<dyn TimeSource>>,
     #[clap(skip)]
-    timer: Option<Timer>,
+    clock: Option<ClockWidget>,
     #[clap(skip)]
-    stopwatch: Option<Stopwatch>,
+    timer: Option<(TimerWidget, TimerState)>,
     #[clap(skip)]
-    countdown: Option<Countdown>,
+    stopwatch: Option<(StopwatchWidget, StopwatchState)>,
+    #[clap(skip)]
+    countdown: Option<CountdownWidget>,
+    #[cfg(unix)]
+    #[clap(skip)]
+    control_server: Option<control::ControlServer>,
+    #[clap(skip)]
+    http_server: Option<HttpServer>,
+    /// Why the session failed to save, shown until it saves again
+    #[clap(skip)]
+    error: Option<String>,
+}
+
+/// What the `status` command answers: the status of the mode, which mode it
+/// is and whether it is paused.
+#[derive(Debug, Clone, Serialize)]
+pub struct State {
+    /// One of clock, timer, stopwatch and countdown
+    pub mode: &'static str,
+    pub paused: bool,
+    #[serde(flatten)]
+    pub status: Status,
+}
+
+/// A time given as an option or in the config. A time of day is on the day
+/// the time source tells, which may not be today with `--now`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeSpec {
+    TimeOfDay(NaiveTime),
+    DateTime(DateTime<Local>),
+}
+
+impl TimeSpec {
+    /// The time this is when it is `now`.
+    pub fn resolve(&self, now: DateTime<Local>) -> DateTime<Local> {
+        match self {
+            TimeSpec::TimeOfDay(time) => on_day_of(now, *time),
+            TimeSpec::DateTime(time) => *time,
+        }
+    }
+}
+
+/// `time` on the day of `now`. A time repeated by a DST change is the first
+/// one, a time skipped by it is as long after `now` as without the change.
+fn on_day_of<Tz: TimeZone>(now: DateTime<Tz>, time: NaiveTime) -> DateTime<Tz> {
+    let time = NaiveDateTime::new(now.date_naive(), time);
+    now.timezone()
+        .from_local_datetime(&time)
+        .earliest()
+        .unwrap_or_else(|| now.clone() + (time - now.naive_local()))
 }
 
 impl App {
-    pub fn set_mode(&mut self, mode: Mode) {
+    /// Uses `time_source` instead of the system time, eg. a `ManualClock` to
+    /// render at a given time. Takes effect on the next `init_app`.
+    pub fn set_time_source(&mut self, time_source: Rc<dyn TimeSource>) {
+        self.time_source = Some(time_source);
+    }
+
+    /// Switches to another mode. The session, if any, is saved and left
+    /// behind, since it belongs to the mode it was started with.
+    pub fn set_mode(&mut self, mode: Mode) -> Result<(), String> {
+        self.error = self.close_session().err();
+        self.session = None;
         self.mode = Some(mode);
-        self.init_app();
+        self.init_app()
     }
 
-    pub fn init_app(&mut self) {
+    pub fn init_app(&mut self) -> Result<(), String> {
         // Load config
-        let config = Config::load();
+        let config = Config::load()?;
         let default_config = config.as_ref().map(|c| &c.default);
+        let time_source = self.time_source.get_or_insert_with(|| match self.now {
+            Some(now) => Rc::new(SystemClock::starting_at(now)),
+            None => Rc::new(SystemClock::default()),
+        });
+        let time_source = time_source.clone();
+        let now = time_source.now();
 
         // default mode
         if self.mode.is_none() {
@@ -154,6 +355,13 @@ impl App {
                         paused: timer_config.map(|c| c.start_paused).unwrap_or(false),
                         auto_quit: timer_config.map(|c| c.auto_quit).unwrap_or(false),
                         execute: timer_config.map(|c| c.execute.clone()).unwrap_or_default(),
+                        progress: timer_config.map(|c| c.show_progress).unwrap_or(false),
+                        total_progress: timer_config
+                            .map(|c| c.show_total_progress)
+                            .unwrap_or(false),
+                        segments: timer_config.map(|c| c.show_segments).unwrap_or(false),
+                        end_time: timer_config.map(|c| c.show_end_time).unwrap_or(false),
+                        end_time_format: None,
                     }
                 }
                 "stopwatch" => Mode::Stopwatch,
@@ -163,13 +371,17 @@ impl App {
                         time: countdown_config
                             .and_then(|c| c.time.as_ref())
                             .and_then(|t| parse_datetime(t).ok())
-                            .unwrap_or_else(|| Local::now()),
+                            .unwrap_or(TimeSpec::DateTime(now)),
                         title: countdown_config.map(|c| c.title.clone()).unwrap_or(None),
                         continue_on_zero: countdown_config
                             .map(|c| c.continue_on_zero)
                             .unwrap_or(false),
                         reverse: countdown_config.map(|c| c.reverse).unwrap_or(false),
                         millis: countdown_config.map(|c| c.show_millis).unwrap_or(false),
+                        progress: countdown_config.map(|c| c.show_progress).unwrap_or(false),
+                        from: countdown_config
+                            .and_then(|c| c.from.as_ref())
+                            .and_then(|t| parse_datetime(t).ok()),
                     }
                 }
                 _ => {
@@ -184,20 +396,79 @@ impl App {
             });
         }
 
-        // set default color and size
+        // set default theme, color and size
+        let theme_config = config.as_ref().map(|c| &c.theme);
+        if self.theme.is_none() {
+            let theme = match theme_config.and_then(|c| c.name.as_ref()) {
+                Some(name) => Theme::load(name)?,
+                None => Theme::default(),
+            };
+            self.theme = Some(match theme_config {
+                Some(theme_config) => theme.with_config(theme_config)?,
+                None => theme,
+            });
+        }
+        let theme = self.theme.clone().unwrap_or_default();
+        let theme_effect = theme.effect;
         if self.color.is_none() {
             self.color = default_config
-                .map(|c| parse_color(&c.color).unwrap_or(Color::Green))
-                .or(Some(Color::Green));
+                .and_then(|c| c.color.clone())
+                .or(Some(theme.foreground.clone()));
         }
         if self.size.is_none() {
-            self.size = default_config.map(|c| c.size).or(Some(1));
+            self.size = default_config
+                .map(|c| check_size(c.size))
+                .transpose()?
+                .or(Some(1.0));
+        }
+
+        if self.font.is_none() {
+            self.font = default_config
+                .map(|c| parse_font(&c.font))
+                .transpose()?
+                .or(Some(FontKind::Bricks));
+        }
+
+        if self.effect.is_none() {
+            self.effect = default_config
+                .and_then(|c| c.effect.as_deref())
+                .map(parse_effect)
+                .transpose()?
+                .or(theme_effect);
+        }
+        if self.transition.is_none() {
+            self.transition = default_config
+                .and_then(|c| c.transition.as_deref())
+                .map(parse_transition)
+                .transpose()?;
         }
 
-        let style = Style::default().fg(self.color.unwrap_or(Color::Green));
-        let size = self.size.unwrap_or(1);
+        let color = self.color.clone().unwrap_or(ColorSpec::Solid(Color::Green));
+        let colors_config = config.as_ref().map(|c| &c.colors);
+        let part_color = |part: fn(&ColorsConfig) -> &Option<ColorSpec>| {
+            colors_config.and_then(|c| part(c).clone())
+        };
+        let look = Look {
+            font: self.font.clone().unwrap_or(FontKind::Bricks),
+            size: self.size.unwrap_or(1.0),
+            effect: self.effect,
+            transition: self.transition,
+            style: Style::default(),
+            colors: Some(TextColors {
+                base: color,
+                hours: part_color(|c| &c.hours),
+                minutes: part_color(|c| &c.minutes),
+                seconds: part_color(|c| &c.seconds),
+                separators: part_color(|c| &c.separators),
+            }),
+            theme,
+        };
 
         // initialize the clock mode
+        self.clock = None;
+        self.timer = None;
+        self.stopwatch = None;
+        self.countdown = None;
         match self.mode.as_ref().unwrap_or(&Mode::Clock {
             no_date: false,
             millis: false,
@@ -211,14 +482,20 @@ impl App {
                 timezone,
             } => {
                 let clock_config = config.as_ref().map(|c| &c.clock);
-                self.clock = Some(Clock {
-                    size,
-                    style,
-                    show_date: !no_date && clock_config.map(|c| c.show_date).unwrap_or(true),
-                    show_millis: *millis || clock_config.map(|c| c.show_millis).unwrap_or(false),
-                    show_secs: !no_seconds && clock_config.map(|c| c.show_seconds).unwrap_or(true),
-                    timezone: timezone.or_else(|| clock_config.and_then(|c| c.timezone)),
-                });
+                self.clock = Some(
+                    ClockWidget::builder()
+                        .look(look)
+                        .show_date(!no_date && clock_config.map(|c| c.show_date).unwrap_or(true))
+                        .show_millis(
+                            *millis || clock_config.map(|c| c.show_millis).unwrap_or(false),
+                        )
+                        .show_seconds(
+                            !no_seconds && clock_config.map(|c| c.show_seconds).unwrap_or(true),
+                        )
+                        .timezone(timezone.or_else(|| clock_config.and_then(|c| c.timezone)))
+                        .time_source(time_source)
+                        .build(),
+                );
             }
             Mode::Timer {
                 durations,
@@ -228,6 +505,11 @@ impl App {
                 paused,
                 auto_quit,
                 execute,
+                progress,
+                total_progress,
+                segments,
+                end_time,
+                end_time_format,
             } => {
                 let timer_config = config.as_ref().map(|c| &c.timer);
                 let format = if *no_millis {
@@ -235,20 +517,58 @@ impl App {
                 } else {
                     DurationFormat::HourMinSecDeci
                 };
-                self.timer = Some(Timer::new(
-                    size,
-                    style,
-                    durations.to_owned(),
-                    titles.to_owned(),
-                    *repeat || timer_config.map(|c| c.repeat).unwrap_or(false),
-                    format,
-                    *paused || timer_config.map(|c| c.start_paused).unwrap_or(false),
-                    *auto_quit || timer_config.map(|c| c.auto_quit).unwrap_or(false),
-                    execute.to_owned(),
-                ));
+                let end_time_format = match end_time_format {
+                    Some(format) => Some(format.to_owned()),
+                    None => timer_config
+                        .and_then(|c| c.end_time_format.as_deref())
+                        .map(parse_end_time_format)
+                        .transpose()?,
+                };
+                let show_end_time = *end_time
+                    || timer_config.map(|c| c.show_end_time).unwrap_or(false)
+                    || end_time_format.is_some();
+                let timer = TimerWidget::builder()
+                    .look(look)
+                    .durations(durations.to_owned())
+                    .titles(titles.to_owned())
+                    .repeat(*repeat || timer_config.map(|c| c.repeat).unwrap_or(false))
+                    .format(format)
+                    .auto_quit(*auto_quit || timer_config.map(|c| c.auto_quit).unwrap_or(false))
+                    .execute(execute.to_owned())
+                    .thresholds(parse_thresholds(
+                        timer_config
+                            .map(|c| c.thresholds.as_slice())
+                            .unwrap_or_default(),
+                    )?)
+                    .show_progress(
+                        *progress || timer_config.map(|c| c.show_progress).unwrap_or(false),
+                    )
+                    .show_total_progress(
+                        *total_progress
+                            || timer_config.map(|c| c.show_total_progress).unwrap_or(false),
+                    )
+                    .show_segments(
+                        *segments || timer_config.map(|c| c.show_segments).unwrap_or(false),
+                    )
+                    .end_time_format(show_end_time.then(|| {
+                        end_time_format.unwrap_or_else(|| DEFAULT_END_TIME_FORMAT.to_string())
+                    }))
+                    .build();
+                let mut state = TimerState::new(time_source);
+                if *paused || timer_config.map(|c| c.start_paused).unwrap_or(false) {
+                    state.pause();
+                }
+                if let Some(session) = self.load_session(SessionKind::Timer)? {
+                    state.restore(&session);
+                }
+                self.timer = Some((timer, state));
             }
             Mode::Stopwatch => {
-                self.stopwatch = Some(Stopwatch::new(size, style));
+                let mut state = StopwatchState::new(time_source);
+                if let Some(session) = self.load_session(SessionKind::Stopwatch)? {
+                    state.restore(&session);
+                }
+                self.stopwatch = Some((StopwatchWidget::builder().look(look).build(), state));
             }
             Mode::Countdown {
                 time,
@@ -256,66 +576,309 @@ impl App {
                 continue_on_zero,
                 reverse,
                 millis,
+                progress,
+                from,
             } => {
                 let countdown_config = config.as_ref().map(|c| &c.countdown);
-                self.countdown = Some(Countdown {
-                    size,
-                    style,
-                    time: *time,
-                    title: title.to_owned(),
-                    continue_on_zero: *continue_on_zero
-                        || countdown_config
-                            .map(|c| c.continue_on_zero)
-                            .unwrap_or(false),
-                    reverse: *reverse || countdown_config.map(|c| c.reverse).unwrap_or(false),
-                    format: if *millis || countdown_config.map(|c| c.show_millis).unwrap_or(false) {
-                        DurationFormat::HourMinSecDeci
-                    } else {
-                        DurationFormat::HourMinSec
-                    },
-                })
+                let from = from.or_else(|| {
+                    countdown_config
+                        .and_then(|c| c.from.as_ref())
+                        .and_then(|t| parse_datetime(t).ok())
+                });
+                self.countdown = Some(
+                    CountdownWidget::builder()
+                        .look(look)
+                        .time(time.resolve(now))
+                        .title(title.to_owned())
+                        .continue_on_zero(
+                            *continue_on_zero
+                                || countdown_config
+                                    .map(|c| c.continue_on_zero)
+                                    .unwrap_or(false),
+                        )
+                        .reverse(*reverse || countdown_config.map(|c| c.reverse).unwrap_or(false))
+                        .format(
+                            if *millis || countdown_config.map(|c| c.show_millis).unwrap_or(false) {
+                                DurationFormat::HourMinSecDeci
+                            } else {
+                                DurationFormat::HourMinSec
+                            },
+                        )
+                        .thresholds(parse_thresholds(
+                            countdown_config
+                                .map(|c| c.thresholds.as_slice())
+                                .unwrap_or_default(),
+                        )?)
+                        .show_progress(
+                            *progress || countdown_config.map(|c| c.show_progress).unwrap_or(false),
+                        )
+                        .progress_from(from.map(|from| from.resolve(now)))
+                        .time_source(time_source)
+                        .build(),
+                )
             }
         }
+        self.start_control()?;
+        if let (Some(addr), None) = (self.http, &self.http_server) {
+            self.http_server = Some(HttpServer::bind(addr)?);
+        }
+        self.save_session()
+    }
+
+    /// Listens on the socket of `--control`, once.
+    #[cfg(unix)]
+    fn start_control(&mut self) -> Result<(), String> {
+        if let (Some(name), None) = (self.control.as_deref(), &self.control_server) {
+            self.control_server = Some(control::ControlServer::bind(name)?);
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn start_control(&mut self) -> Result<(), String> {
+        match self.control {
+            Some(_) => Err("--control is only supported on Unix".to_string()),
+            None => Ok(()),
+        }
+    }
+
+    /// Loads the state of `--session`, if it was saved by the same mode.
+    fn load_session(&self, kind: SessionKind) -> Result<Option<SessionState>, String> {
+        let Some(name) = self.session.as_deref() else {
+            return Ok(None);
+        };
+        Ok(SessionState::load(name)?.filter(|state| state.kind == kind))
+    }
+
+    fn save_session(&self) -> Result<(), String> {
+        let Some(name) = self.session.as_deref() else {
+            return Ok(());
+        };
+        let state = if let Some((_, ref state)) = self.timer {
+            state.session_state()
+        } else if let Some((_, ref state)) = self.stopwatch {
+            state.session_state()
+        } else {
+            return Ok(());
+        };
+        state.save(name)
+    }
+
+    /// Saves the session for the next run, or forgets it if the timer is
+    /// finished so the next run starts over.
+    fn close_session(&self) -> Result<(), String> {
+        match (self.session.as_deref(), &self.timer) {
+            (Some(name), Some((w, state))) if w.is_over(state) => SessionState::remove(name),
+            _ => self.save_session(),
+        }
+    }
+
+    /// Why the session failed to save, if it did since the last call.
+    pub fn take_error(&mut self) -> Option<String> {
+        self.error.take()
+    }
+
+    /// Runs what the mode does on its own, like the command of a finished
+    /// timer. Drawing the app does it too, so this is for when it is not drawn.
+    pub fn tick(&mut self) {
+        if let Some((ref w, ref mut state)) = self.timer {
+            w.tick(state);
+        }
     }
 
-    pub fn ui(&self, f: &mut Frame) {
+    /// What the current mode shows, as text.
+    pub fn status(&self) -> Option<Status> {
+        if let Some(ref w) = self.clock {
+            Some(w.status())
+        } else if let Some((ref w, ref state)) = self.timer {
+            Some(w.status(state))
+        } else if let Some((ref w, ref state)) = self.stopwatch {
+            Some(w.status(state))
+        } else {
+            self.countdown.as_ref().map(|w| w.status())
+        }
+    }
+
+    /// How to print the status line instead of drawing the clock, if at all.
+    pub fn bar_format(&self) -> Option<BarFormat> {
+        if self.i3bar {
+            Some(BarFormat::I3bar)
+        } else if self.waybar {
+            Some(BarFormat::Waybar)
+        } else if self.plain || self.template.is_some() {
+            Some(BarFormat::Plain)
+        } else {
+            None
+        }
+    }
+
+    /// The status line of `--plain`, `--i3bar` or `--waybar`.
+    pub fn status_line(&self, format: BarFormat) -> Option<String> {
+        let template = self
+            .template
+            .clone()
+            .unwrap_or_else(|| DEFAULT_STATUS_TEMPLATE.to_string());
+        self.status()
+            .map(|status| format.line(&status, status.format(&template)))
+    }
+
+    /// Does `action` to the timer or stopwatch, fails if the mode has no such action.
+    pub fn on_action(&mut self, action: Action) -> Result<(), String> {
+        if let Some((w, state)) = self.timer.as_mut() {
+            match action {
+                Action::Pause => state.pause(),
+                Action::Resume => state.resume(),
+                Action::TogglePause => state.toggle_paused(),
+                Action::Add(duration) => w.add(state, duration)?,
+                Action::Skip => w.skip(state),
+                Action::Lap => return Err("Only a stopwatch has laps".to_string()),
+                Action::Reset => state.reset(),
+            }
+        } else if let Some((_, state)) = self.stopwatch.as_mut() {
+            match action {
+                Action::Pause => state.pause(),
+                Action::Resume => state.resume(),
+                Action::TogglePause => state.toggle_paused(),
+                Action::Add(duration) => state.add(duration)?,
+                Action::Skip => return Err("Only a timer can skip".to_string()),
+                Action::Lap => state.lap(),
+                Action::Reset => state.reset(),
+            }
+        } else {
+            return Err("Only a timer or a stopwatch takes commands".to_string());
+        }
+        // not an error of the action, which is done already
+        self.error = self.save_session().err();
+        Ok(())
+    }
+
+    /// Answers the commands received on the socket of `--control` and by the
+    /// HTTP API of `--http`, if any.
+    pub fn poll_control(&mut self) {
+        let mut requests: Vec<Request> = Vec::new();
+        #[cfg(unix)]
+        if let Some(ref server) = self.control_server {
+            requests.extend(server.requests());
+        }
+        if let Some(ref server) = self.http_server {
+            requests.extend(server.requests());
+        }
+        for request in requests {
+            let result = self.on_command(&request.command);
+            request.reply(result);
+        }
+    }
+
+    /// Does a command like `pause` or `add 5m`, see [`Action`], and answers
+    /// `ok`. The `status` command answers the [`State`] as JSON instead.
+    pub fn on_command(&mut self, command: &str) -> Result<String, String> {
+        match command.trim() {
+            "status" => {
+                let state = self.state().ok_or_else(|| "Nothing to show".to_string())?;
+                serde_json::to_string(&state).map_err(|e| e.to_string())
+            }
+            command => {
+                self.on_action(command.parse()?)?;
+                Ok("ok".to_string())
+            }
+        }
+    }
+
+    /// The status of the current mode, with which mode it is.
+    pub fn state(&self) -> Option<State> {
+        let mode = if self.clock.is_some() {
+            "clock"
+        } else if self.timer.is_some() {
+            "timer"
+        } else if self.stopwatch.is_some() {
+            "stopwatch"
+        } else {
+            "countdown"
+        };
+        let paused = match (&self.timer, &self.stopwatch) {
+            (Some((_, state)), _) => state.is_paused(),
+            (_, Some((_, state))) => state.is_paused(),
+            _ => false,
+        };
+        self.status().map(|status| State {
+            mode,
+            paused,
+            status,
+        })
+    }
+
+    pub fn ui(&mut self, f: &mut Frame) {
         if let Some(ref w) = self.clock {
             f.render_widget(w, f.size());
-        } else if let Some(ref w) = self.timer {
-            f.render_widget(w, f.size());
-        } else if let Some(ref w) = self.stopwatch {
-            f.render_widget(w, f.size());
+        } else if let Some((ref w, ref mut state)) = self.timer {
+            f.render_stateful_widget(w, f.size(), state);
+        } else if let Some((ref w, ref mut state)) = self.stopwatch {
+            f.render_stateful_widget(w, f.size(), state);
         } else if let Some(ref w) = self.countdown {
             f.render_widget(w, f.size());
         }
+        if let Some(ref error) = self.error {
+            let area = f.size();
+            let area = Rect::new(area.x, area.bottom().saturating_sub(1), area.width, 1);
+            let style = self.theme.as_ref().map(|t| t.warning).unwrap_or_default();
+            f.render_widget(Clear, area);
+            f.render_widget(Paragraph::new(error.as_str()).style(style), area);
+        }
+    }
+
+    /// How long the event loop may wait before drawing the next frame.
+    pub fn tick_rate(&self) -> std::time::Duration {
+        let transition = if let Some(ref w) = self.clock {
+            w.transition.as_ref()
+        } else if let Some((ref w, _)) = self.timer {
+            w.transition.as_ref()
+        } else if let Some((ref w, _)) = self.stopwatch {
+            w.transition.as_ref()
+        } else if let Some(ref w) = self.countdown {
+            w.transition.as_ref()
+        } else {
+            None
+        };
+        if transition.is_some_and(|t| t.is_animating()) {
+            std::time::Duration::from_millis(30)
+        } else {
+            std::time::Duration::from_millis(250)
+        }
     }
 
     pub fn on_key(&mut self, key: KeyCode) {
-        if let Some(_w) = self.clock.as_mut() {
-        } else if let Some(w) = self.timer.as_mut() {
-            handle_key(w, key);
-        } else if let Some(w) = self.stopwatch.as_mut() {
-            handle_key(w, key);
+        if let KeyCode::Char(' ') = key {
+            // only a timer or a stopwatch can be paused
+            let _ = self.on_action(Action::TogglePause);
         }
     }
 
     pub fn is_ended(&self) -> bool {
-        if let Some(ref w) = self.timer {
-            return w.is_finished();
+        if let Some((ref w, ref state)) = self.timer {
+            return w.is_finished(state);
         }
         false
     }
 
+    /// Prints the laps and time of a stopwatch, unless the status line went to
+    /// a bar which would take them for one, and saves or forgets the session.
     pub fn on_exit(&self) {
-        if let Some(ref w) = self.stopwatch {
-            println!("Stopwatch time: {}", w.get_display_time());
+        if let (Some((_, ref state)), None) = (&self.stopwatch, self.bar_format()) {
+            for (i, lap) in state.laps().iter().enumerate() {
+                println!(
+                    "Lap {}: {}",
+                    i + 1,
+                    format_duration(*lap, DurationFormat::HourMinSecDeci)
+                );
+            }
+            println!(
+                "Stopwatch time: {}",
+                format_duration(state.elapsed(), DurationFormat::HourMinSecDeci)
+            );
+        }
+        if let Err(e) = self.close_session() {
+            eprintln!("{}", e);
         }
-    }
-}
-
-fn handle_key<T: Pause>(widget: &mut T, key: KeyCode) {
-    if let KeyCode::Char(' ') = key {
-        widget.toggle_paused()
     }
 }
 
@@ -325,81 +888,165 @@ fn parse_duration(s: &str) -> Result<Duration, String> {
         .captures(s)
         .ok_or_else(|| format!("{} is not a valid duration", s))?;
 
-    let num = cap.get(1).unwrap().as_str().parse::<i64>().unwrap();
-    let unit = cap.get(2).unwrap().as_str().to_lowercase();
+    let too_long = || format!("{} is too long, at most {}d", s, MAX_DURATION.num_days());
+    let num = cap[1].parse::<i64>().map_err(|_| too_long())?;
+    let duration = match cap[2].to_lowercase().as_str() {
+        "s" => Duration::try_seconds(num),
+        "m" => Duration::try_minutes(num),
+        "h" => Duration::try_hours(num),
+        "d" => Duration::try_days(num),
+        _ => return Err(format!("Invalid duration: {}", s)),
+    };
+    duration.filter(|d| *d <= MAX_DURATION).ok_or_else(too_long)
+}
 
-    match unit.as_str() {
-        "s" => Ok(Duration::seconds(num)),
-        "m" => Ok(Duration::minutes(num)),
-        "h" => Ok(Duration::hours(num)),
-        "d" => Ok(Duration::days(num)),
-        _ => Err(format!("Invalid duration: {}", s)),
+fn parse_size(s: &str) -> Result<f32, String> {
+    s.parse()
+        .map_err(|_| format!("Invalid size: {}", s))
+        .and_then(check_size)
+}
+
+fn check_size(size: f32) -> Result<f32, String> {
+    if size.is_finite() && size >= 1.0 {
+        Ok(size)
+    } else {
+        Err(format!("Invalid size: {}, should be at least 1", size))
     }
 }
 
-fn parse_color(s: &str) -> Result<Color, String> {
-    let s = s.to_lowercase();
-    let reg = Regex::new(r"^#([0-9a-f]{6})$").unwrap();
-    match s.as_str() {
-        "black" => Ok(Color::Black),
-        "red" => Ok(Color::Red),
-        "green" => Ok(Color::Green),
-        "yellow" => Ok(Color::Yellow),
-        "blue" => Ok(Color::Blue),
-        "magenta" => Ok(Color::Magenta),
-        "cyan" => Ok(Color::Cyan),
-        "gray" => Ok(Color::Gray),
-        "darkgray" => Ok(Color::DarkGray),
-        "lightred" => Ok(Color::LightRed),
-        "lightgreen" => Ok(Color::LightGreen),
-        "lightyellow" => Ok(Color::LightYellow),
-        "lightblue" => Ok(Color::LightBlue),
-        "lightmagenta" => Ok(Color::LightMagenta),
-        "lightcyan" => Ok(Color::LightCyan),
-        "white" => Ok(Color::White),
-        s => {
-            let cap = reg
-                .captures(s)
-                .ok_or_else(|| format!("Invalid color: {}", s))?;
-            let hex = cap.get(1).unwrap().as_str();
-            let r = u8::from_str_radix(&hex[0..2], 16).unwrap();
-            let g = u8::from_str_radix(&hex[2..4], 16).unwrap();
-            let b = u8::from_str_radix(&hex[4..], 16).unwrap();
-            Ok(Color::Rgb(r, g, b))
+fn parse_name(s: &str) -> Result<String, String> {
+    let valid = s
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if s.is_empty() || s.starts_with('.') || !valid {
+        return Err(format!(
+            "Invalid name: {}, use letters, digits, '-', '_' and '.'",
+            s
+        ));
+    }
+    Ok(s.to_string())
+}
+
+fn parse_socket_addr(s: &str) -> Result<SocketAddr, String> {
+    s.parse()
+        .map_err(|_| format!("Invalid address: {}, eg. 127.0.0.1:7878", s))
+}
+
+fn parse_thresholds(configs: &[ThresholdConfig]) -> Result<Vec<Threshold>, String> {
+    configs
+        .iter()
+        .map(|c| {
+            Ok(Threshold {
+                remaining: parse_duration(&c.remaining)
+                    .map_err(|e| format!("Invalid threshold: {}", e))?,
+                color: c.color.clone(),
+                blink: c.blink,
+            })
+        })
+        .collect()
+}
+
+fn parse_font(s: &str) -> Result<FontKind, String> {
+    match s.to_lowercase().as_str() {
+        "bricks" => Ok(FontKind::Bricks),
+        "half-block" | "halfblock" => Ok(FontKind::HalfBlock),
+        "braille" => Ok(FontKind::Braille),
+        _ => {
+            let path = Path::new(s);
+            let path = if path.extension().is_some() || path.components().count() > 1 {
+                path.to_path_buf()
+            } else {
+                let fonts_dir = config::config_dir()
+                    .map(|dir| dir.join("fonts"))
+                    .ok_or_else(|| format!("Invalid font: {}", s))?;
+                let figlet = fonts_dir.join(format!("{}.flf", s));
+                if figlet.exists() {
+                    figlet
+                } else {
+                    fonts_dir.join(format!("{}.toml", s))
+                }
+            };
+            if !path.exists() {
+                return Err(format!("Invalid font: {}, {} not found", s, path.display()));
+            }
+            if path.extension().is_some_and(|ext| ext == "toml") {
+                BricksFontConfig::load(&path).map(|c| {
+                    if c.half_block {
+                        FontKind::UserHalfBlock(c.to_glyphs())
+                    } else {
+                        FontKind::UserBricks(c.to_glyphs())
+                    }
+                })
+            } else {
+                FigletFont::load(&path).map(FontKind::Figlet)
+            }
         }
     }
 }
 
-fn parse_datetime(s: &str) -> Result<DateTime<Local>, String> {
+fn parse_effect(s: &str) -> Result<Effect, String> {
+    match s.to_lowercase().as_str() {
+        "shadow" => Ok(Effect::Shadow),
+        "outline" => Ok(Effect::Outline),
+        "3d" | "extrude" => Ok(Effect::Extrude),
+        _ => Err(format!("Invalid effect: {}", s)),
+    }
+}
+
+fn parse_transition(s: &str) -> Result<Transition, String> {
+    match s.to_lowercase().as_str() {
+        "flip" => Ok(Transition::Flip),
+        "roll" => Ok(Transition::Roll),
+        "dissolve" => Ok(Transition::Dissolve),
+        _ => Err(format!("Invalid transition: {}", s)),
+    }
+}
+
+/// The time of `--now`, a time of day being today.
+fn parse_now(s: &str) -> Result<DateTime<Local>, String> {
+    parse_datetime(s).map(|time| time.resolve(Local::now()))
+}
+
+fn parse_datetime(s: &str) -> Result<TimeSpec, String> {
     let s = s.trim();
-    let today = Local::now().date_naive();
 
     let time = NaiveTime::parse_from_str(s, "%H:%M");
     if let Ok(time) = time {
-        let time = NaiveDateTime::new(today, time);
-        return Ok(Local.from_local_datetime(&time).unwrap());
+        return Ok(TimeSpec::TimeOfDay(time));
     }
 
     let time = NaiveTime::parse_from_str(s, "%H:%M:%S");
     if let Ok(time) = time {
-        let time = NaiveDateTime::new(today, time);
-        return Ok(Local.from_local_datetime(&time).unwrap());
+        return Ok(TimeSpec::TimeOfDay(time));
     }
 
+    // The first of two times repeated by a DST change, none skipped by it
+    let local = |date_time: NaiveDateTime| {
+        Local
+            .from_local_datetime(&date_time)
+            .earliest()
+            .map(TimeSpec::DateTime)
+            .ok_or_else(|| format!("{} does not exist in the local time zone", s))
+    };
+
     let date = NaiveDate::parse_from_str(s, "%Y-%m-%d");
     if let Ok(date) = date {
-        let time = NaiveDateTime::new(date, NaiveTime::from_hms_opt(0, 0, 0).unwrap());
-        return Ok(Local.from_local_datetime(&time).unwrap());
+        return local(NaiveDateTime::new(date, NaiveTime::MIN));
     }
 
     let date_time = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S");
     if let Ok(date_time) = date_time {
-        return Ok(Local.from_local_datetime(&date_time).unwrap());
+        return local(date_time);
+    }
+
+    let date_time = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M");
+    if let Ok(date_time) = date_time {
+        return local(date_time);
     }
 
     let rfc_time = DateTime::parse_from_rfc3339(s);
     if let Ok(rfc_time) = rfc_time {
-        return Ok(rfc_time.with_timezone(&Local));
+        return Ok(TimeSpec::DateTime(rfc_time.with_timezone(&Local)));
     }
 
     Err("Invalid time format".to_string())
@@ -408,3 +1055,68 @@ fn parse_datetime(s: &str) -> Result<DateTime<Local>, String> {
 fn parse_timezone(s: &str) -> Result<Tz, String> {
     s.parse()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_duration_units() {
+        assert_eq!(parse_duration("90s"), Ok(Duration::seconds(90)));
+        assert_eq!(parse_duration("5m"), Ok(Duration::minutes(5)));
+        assert_eq!(parse_duration("2H"), Ok(Duration::hours(2)));
+        assert_eq!(parse_duration("1d"), Ok(Duration::days(1)));
+        assert_eq!(parse_duration("0s"), Ok(Duration::zero()));
+    }
+
+    #[test]
+    fn parse_duration_rejects_garbage() {
+        for s in [
+            "", "5", "m", "5x", "-5m", "5 m", " 5m", "5m5s", "1.5h", "５m",
+        ] {
+            assert!(parse_duration(s).is_err(), "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn parse_duration_rejects_overflow() {
+        for s in [
+            "99999999999999999999s",
+            "9223372036854775807s",
+            "9223372036854775807d",
+            "153722867280912931m",
+            "36501d",
+        ] {
+            assert!(parse_duration(s).is_err(), "{:?}", s);
+        }
+        assert_eq!(parse_duration("36500d"), Ok(MAX_DURATION));
+    }
+
+    #[test]
+    fn times_of_day_around_dst_changes() {
+        let berlin = chrono_tz::Europe::Berlin;
+        let time = NaiveTime::from_hms_opt(2, 30, 0).unwrap();
+        let utc = |month, day, hour, min| {
+            chrono::Utc
+                .with_ymd_and_hms(2025, month, day, hour, min, 0)
+                .unwrap()
+        };
+
+        // 02:30 is skipped in spring, it is 1h30 after 01:00
+        let now = berlin.with_ymd_and_hms(2025, 3, 30, 1, 0, 0).unwrap();
+        assert_eq!(on_day_of(now, time), utc(3, 30, 1, 30));
+
+        // and is the first 02:30 in autumn
+        let now = berlin.with_ymd_and_hms(2025, 10, 26, 1, 0, 0).unwrap();
+        assert_eq!(on_day_of(now, time), utc(10, 26, 0, 30));
+    }
+
+    #[test]
+    fn parse_sizes() {
+        assert_eq!(parse_size("1"), Ok(1.0));
+        assert_eq!(parse_size("1.5"), Ok(1.5));
+        for s in ["", "big", "0.5", "0", "-2", "NaN", "inf"] {
+            assert!(parse_size(s).is_err(), "{:?}", s);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use chrono::Duration;
+
+use super::parse_duration;
+
+/// Something done to the timer or stopwatch, from a key press, a click or a
+/// command of the control socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Pause,
+    Resume,
+    TogglePause,
+    /// Add time to the current duration of a timer, or to a stopwatch
+    Add(Duration),
+    /// Go on with the next duration of a timer
+    Skip,
+    /// Record the time of a stopwatch as a lap
+    Lap,
+    /// Start over from zero
+    Reset,
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    /// Parses a command like `pause` or `add 5m`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+        let action = match (words.next(), words.next()) {
+            (Some("pause"), None) => Action::Pause,
+            (Some("resume"), None) => Action::Resume,
+            (Some("toggle"), None) => Action::TogglePause,
+            (Some("add"), Some(duration)) => Action::Add(parse_duration(duration)?),
+            (Some("skip"), None) => Action::Skip,
+            (Some("lap"), None) => Action::Lap,
+            (Some("reset"), None) => Action::Reset,
+            _ => return Err(format!("Unknown command: {}", s.trim())),
+        };
+        match words.next() {
+            Some(_) => Err(format!("Unknown command: {}", s.trim())),
+            None => Ok(action),
+        }
+    }
+}
+
+/// A command like `pause` or `status` received by the control socket or the
+/// HTTP API on another thread, answered by the app between frames.
+pub struct Request {
+    pub command: String,
+    reply: Sender<Result<String, String>>,
+}
+
+impl Request {
+    /// A request of `command`, and where its reply is received.
+    pub fn new(command: String) -> (Self, Receiver<Result<String, String>>) {
+        let (tx, rx) = mpsc::channel();
+        (Self { command, reply: tx }, rx)
+    }
+
+    /// Sends the reply back, the sender may have gone already.
+    pub fn reply(self, result: Result<String, String>) {
+        let _ = self.reply.send(result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!("pause".parse(), Ok(Action::Pause));
+        assert_eq!("resume".parse(), Ok(Action::Resume));
+        assert_eq!(" toggle\n".parse(), Ok(Action::TogglePause));
+        assert_eq!("add 5m".parse(), Ok(Action::Add(Duration::minutes(5))));
+        assert_eq!("add   90s".parse(), Ok(Action::Add(Duration::seconds(90))));
+        assert_eq!("skip".parse(), Ok(Action::Skip));
+        assert_eq!("lap".parse(), Ok(Action::Lap));
+        assert_eq!("reset".parse(), Ok(Action::Reset));
+    }
+
+    #[test]
+    fn parse_garbage() {
+        for s in [
+            "",
+            "PAUSE",
+            "status",
+            "pause now",
+            "add",
+            "add 5",
+            "add 5m 5m",
+            "add -5m",
+            "\u{0}",
+        ] {
+            assert!(s.parse::<Action>().is_err(), "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn parse_overflowing_durations() {
+        for s in ["add 99999999999999999999s", "add 9223372036854775807d"] {
+            assert!(s.parse::<Action>().is_err(), "{:?}", s);
+        }
+    }
+}
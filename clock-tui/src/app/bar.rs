@@ -0,0 +1,112 @@
+use serde::Deserialize;
+use serde_json::json;
+
+use super::action::Action;
+use super::modes::{RunState, Status};
+
+/// How the status line is printed, see `--plain`, `--i3bar` and `--waybar`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BarFormat {
+    /// The text only
+    Plain,
+    /// Blocks of the i3bar protocol, with click events read from stdin
+    I3bar,
+    /// JSON of a waybar custom module with `"return-type": "json"`
+    Waybar,
+}
+
+impl BarFormat {
+    /// Printed once before the first line. For i3bar it opens the endless
+    /// array of lines with an empty one, so every line can start with a comma.
+    pub fn header(&self) -> Option<String> {
+        match self {
+            BarFormat::I3bar => Some(format!(
+                "{}\n[\n[]",
+                json!({"version": 1, "click_events": true})
+            )),
+            _ => None,
+        }
+    }
+
+    /// The line printed for `status`, with `text` filled from the template.
+    pub fn line(&self, status: &Status, text: String) -> String {
+        match self {
+            BarFormat::Plain => text,
+            BarFormat::I3bar => {
+                let block = json!({
+                    "name": "tclock",
+                    "full_text": text,
+                    "urgent": status.state == RunState::Finished,
+                });
+                format!(",[{}]", block)
+            }
+            BarFormat::Waybar => {
+                let mut module = json!({
+                    "text": text,
+                    "tooltip": status.tooltip.clone().unwrap_or_default(),
+                    "class": status.state.name(),
+                });
+                if let Some(percentage) = status.percentage {
+                    module["percentage"] = json!(percentage);
+                }
+                module.to_string()
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ClickEvent {
+    button: u8,
+}
+
+/// The action of an i3bar click event read from stdin: the left button
+/// toggles pause, the middle one resets and the right one skips.
+pub fn parse_click(line: &str) -> Option<Action> {
+    // Events are items of an endless array, after a line with only "["
+    let line = line.trim().trim_start_matches(['[', ',']).trim();
+    let event: ClickEvent = serde_json::from_str(line).ok()?;
+    match event.button {
+        1 => Some(Action::TogglePause),
+        2 => Some(Action::Reset),
+        3 => Some(Action::Skip),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_clicks() {
+        let event = |button| format!(r#"{{"name":"tclock","button":{},"x":10,"y":5}}"#, button);
+        assert_eq!(parse_click(&event(1)), Some(Action::TogglePause));
+        assert_eq!(parse_click(&event(2)), Some(Action::Reset));
+        assert_eq!(parse_click(&event(3)), Some(Action::Skip));
+        // scrolling does nothing
+        assert_eq!(parse_click(&event(4)), None);
+    }
+
+    #[test]
+    fn parse_clicks_in_the_endless_array() {
+        assert_eq!(parse_click("["), None);
+        assert_eq!(parse_click(r#"[{"button":1}"#), Some(Action::TogglePause));
+        assert_eq!(parse_click(r#",{"button":2}"#), Some(Action::Reset));
+        assert_eq!(parse_click(" , {\"button\":3}\n"), Some(Action::Skip));
+    }
+
+    #[test]
+    fn parse_garbage_clicks() {
+        for line in [
+            "",
+            ",",
+            "]",
+            "{",
+            r#"{"name":"tclock"}"#,
+            r#"{"button":"1"}"#,
+        ] {
+            assert_eq!(parse_click(line), None, "{:?}", line);
+        }
+    }
+}
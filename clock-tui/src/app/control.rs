@@ -0,0 +1,172 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use super::action::Request;
+
+/// The directory of the control sockets, i.e. `$XDG_RUNTIME_DIR/tclock`, or
+/// `tclock-<uid>` in the temporary directory shared by all users without it.
+pub fn socket_dir() -> PathBuf {
+    match dirs::runtime_dir() {
+        Some(dir) => dir.join("tclock"),
+        None => std::env::temp_dir().join(format!("tclock-{}", uid())),
+    }
+}
+
+fn socket_path(name: &str) -> PathBuf {
+    socket_dir().join(format!("{}.sock", name))
+}
+
+fn uid() -> u32 {
+    // SAFETY: getuid has no preconditions and cannot fail
+    unsafe { libc::getuid() }
+}
+
+/// Creates the socket directory, only the current user may use it.
+fn create_socket_dir(dir: &Path) -> Result<(), String> {
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    check_socket_dir(dir)
+}
+
+/// Fails unless `dir` is a directory of the current user that others can not
+/// get into, so no one else can take over or listen in on its sockets.
+fn check_socket_dir(dir: &Path) -> Result<(), String> {
+    let metadata = fs::symlink_metadata(dir)
+        .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    if !metadata.is_dir() || metadata.uid() != uid() {
+        return Err(format!(
+            "{} is not a directory of the current user",
+            dir.display()
+        ));
+    }
+    if metadata.mode() & 0o077 != 0 {
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700))
+            .map_err(|e| format!("Failed to make {} private: {}", dir.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Listens on the socket of `--control`, one command per line answered with
+/// one line: `ok`, `error: ` and why, or the reply of `status`. Commands are
+/// read on other threads and handed over to the app, which polls them with
+/// `requests` between frames. The socket is removed when it is dropped.
+pub struct ControlServer {
+    path: PathBuf,
+    requests: Receiver<Request>,
+}
+
+impl ControlServer {
+    pub fn bind(name: &str) -> Result<Self, String> {
+        create_socket_dir(&socket_dir())?;
+        let path = socket_path(name);
+        if path.exists() {
+            // Left over by a tclock that did not exit cleanly, unless it answers
+            if UnixStream::connect(&path).is_ok() {
+                return Err(format!(
+                    "A tclock is already listening on {}",
+                    path.display()
+                ));
+            }
+            let _ = fs::remove_file(&path);
+        }
+        let listener = UnixListener::bind(&path)
+            .map_err(|e| format!("Failed to listen on {}: {}", path.display(), e))?;
+
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let tx = tx.clone();
+                std::thread::spawn(move || serve(stream, tx));
+            }
+        });
+        Ok(Self { path, requests: rx })
+    }
+
+    /// The commands received since the last call.
+    pub fn requests(&self) -> impl Iterator<Item = Request> + '_ {
+        self.requests.try_iter()
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Answers the commands of one client until it closes the connection.
+fn serve(stream: UnixStream, requests: Sender<Request>) {
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    for line in BufReader::new(stream).lines() {
+        let Ok(command) = line else { break };
+        if command.trim().is_empty() {
+            continue;
+        }
+        let (request, reply) = Request::new(command);
+        // The app has exited when these fail
+        if requests.send(request).is_err() {
+            break;
+        }
+        let reply = match reply.recv() {
+            Ok(Ok(reply)) => reply,
+            Ok(Err(e)) => format!("error: {}", e),
+            Err(_) => break,
+        };
+        if writeln!(writer, "{}", reply).is_err() {
+            break;
+        }
+    }
+}
+
+/// Sends `command` to the tclock listening with `--control name`, and
+/// returns its reply.
+pub fn send(name: &str, command: &str) -> Result<String, String> {
+    let path = socket_path(name);
+    if path.exists() {
+        check_socket_dir(&socket_dir())?;
+    }
+    let mut stream = UnixStream::connect(&path)
+        .map_err(|e| format!("Failed to connect to {}: {}", path.display(), e))?;
+    writeln!(stream, "{}", command)
+        .and_then(|_| stream.shutdown(std::net::Shutdown::Write))
+        .map_err(|e| format!("Failed to send the command: {}", e))?;
+    let mut reply = String::new();
+    stream
+        .read_to_string(&mut reply)
+        .map_err(|e| format!("Failed to read the reply: {}", e))?;
+    Ok(reply.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn socket_dirs_are_private() {
+        let base = std::env::temp_dir().join(format!("tclock-test-{}-control", std::process::id()));
+        let dir = base.join("tclock");
+        create_socket_dir(&dir).unwrap();
+        let mode = |dir: &Path| fs::metadata(dir).unwrap().mode() & 0o777;
+        assert_eq!(mode(&dir), 0o700);
+
+        // made private again if opened up
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o777)).unwrap();
+        create_socket_dir(&dir).unwrap();
+        assert_eq!(mode(&dir), 0o700);
+
+        // nor followed if a link
+        let link = base.join("link");
+        std::os::unix::fs::symlink(&dir, &link).unwrap();
+        assert!(check_socket_dir(&link).is_err());
+        fs::remove_dir_all(&base).unwrap();
+    }
+}
@@ -0,0 +1,233 @@
+use std::io::Read;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use tiny_http::{Header, Method, Response, Server};
+
+use super::action::Request;
+
+/// The page served at `/`, polling `/state` and posting the buttons.
+const PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>tclock</title>
+<style>
+body { background: #000; color: #0c0; font-family: monospace; text-align: center; margin-top: 15vh; }
+#time { font-size: 16vw; }
+#title, #state { color: #888; font-size: 5vw; }
+.paused #time { color: #cc0; }
+.warning #time { color: #c60; }
+.finished #time { color: #c00; }
+button { font: inherit; font-size: 4vw; margin: 1vw; }
+</style>
+</head>
+<body>
+<div id="title"></div>
+<div id="time"></div>
+<div id="state"></div>
+<div id="buttons" hidden>
+<button onclick="post('toggle')">pause/resume</button>
+<button onclick="post('add', '1m')">+1m</button>
+<button onclick="post('add', '5m')">+5m</button>
+<button onclick="post('skip')" data-mode="timer">skip</button>
+<button onclick="post('lap')" data-mode="stopwatch">lap</button>
+<button onclick="post('reset')">reset</button>
+</div>
+<script>
+const $ = (id) => document.getElementById(id);
+async function post(command, body) {
+  await fetch('/' + command, { method: 'POST', body });
+  refresh();
+}
+async function refresh() {
+  try {
+    const state = await (await fetch('/state')).json();
+    document.body.className = state.state;
+    $('title').textContent = state.title || state.date || '';
+    $('time').textContent = state.remaining || state.elapsed || state.time || '';
+    $('state').textContent = state.state;
+    $('buttons').hidden = !['timer', 'stopwatch'].includes(state.mode);
+    for (const button of document.querySelectorAll('[data-mode]')) {
+      button.hidden = button.dataset.mode !== state.mode;
+    }
+  } catch (e) {
+    $('state').textContent = 'disconnected';
+  }
+}
+refresh();
+setInterval(refresh, 250);
+</script>
+</body>
+</html>
+"#;
+
+/// The commands posted to `/<command>`, `add` taking the duration as body.
+const COMMANDS: &[&str] = &["pause", "resume", "toggle", "add", "skip", "lap", "reset"];
+
+/// Serves the HTTP API of `--http`: `GET /state` answers the status as JSON,
+/// `POST /pause`, `/resume`, `/toggle`, `/skip`, `/lap`, `/reset` and `/add`
+/// with a duration like `5m` as body do the same as the commands of the
+/// control socket, and `GET /` is a page showing the state. Requests are
+/// served on another thread and handed over to the app, which polls them
+/// with `requests` between frames.
+///
+/// There is no authentication, but browsers are kept from controlling the
+/// clock on behalf of other sites: commands posted from a page of another
+/// origin are refused, and so are requests for another host name than
+/// localhost when listening on a loopback address.
+pub struct HttpServer {
+    requests: Receiver<Request>,
+}
+
+impl HttpServer {
+    pub fn bind(addr: SocketAddr) -> Result<Self, String> {
+        let server =
+            Server::http(addr).map_err(|e| format!("Failed to listen on {}: {}", addr, e))?;
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || serve(server, addr, tx));
+        Ok(Self { requests: rx })
+    }
+
+    /// The commands received since the last call.
+    pub fn requests(&self) -> impl Iterator<Item = Request> + '_ {
+        self.requests.try_iter()
+    }
+}
+
+fn serve(server: Server, addr: SocketAddr, requests: Sender<Request>) {
+    for mut request in server.incoming_requests() {
+        if let Err(e) = check_origin(&request, addr) {
+            let _ = request.respond(Response::from_string(e + "\n").with_status_code(403));
+            continue;
+        }
+        let path = request
+            .url()
+            .split('?')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let name = path.strip_prefix('/').unwrap_or_default();
+        let command = match (request.method(), path.as_str()) {
+            (Method::Get, "/") => {
+                let _ = request.respond(with_type(Response::from_string(PAGE), "text/html"));
+                continue;
+            }
+            (Method::Get, "/state") => Ok("status".to_string()),
+            (Method::Post, "/add") => {
+                let mut body = String::new();
+                match request.as_reader().take(64).read_to_string(&mut body) {
+                    Ok(_) => match body.split_whitespace().collect::<Vec<_>>()[..] {
+                        [duration] => Ok(format!("add {}", duration)),
+                        _ => Err((400, "Expected a duration like 5m as body".to_string())),
+                    },
+                    Err(e) => Err((400, e.to_string())),
+                }
+            }
+            (Method::Post, _) if COMMANDS.contains(&name) => Ok(name.to_string()),
+            (_, "/state") => Err((405, "Use GET".to_string())),
+            _ if COMMANDS.contains(&name) => Err((405, "Use POST".to_string())),
+            _ => Err((404, "Not found".to_string())),
+        };
+        let response = command.and_then(|command| {
+            let (request, reply) = Request::new(command);
+            // The app has exited when these fail
+            requests
+                .send(request)
+                .ok()
+                .and_then(|_| reply.recv().ok())
+                .ok_or_else(|| (503, "tclock is exiting".to_string()))?
+                .map_err(|e| (400, e))
+        });
+        let _ = match response {
+            Ok(body) if path == "/state" => {
+                request.respond(with_type(Response::from_string(body), "application/json"))
+            }
+            Ok(body) => request.respond(Response::from_string(body + "\n")),
+            Err((status, e)) => {
+                request.respond(Response::from_string(e + "\n").with_status_code(status))
+            }
+        };
+    }
+}
+
+/// Refuses commands posted by pages of other origins, and any request for
+/// another host than localhost when listening on a loopback address, as done
+/// by pages of other sites through DNS rebinding.
+fn check_origin(request: &tiny_http::Request, addr: SocketAddr) -> Result<(), String> {
+    let header = |name: &'static str| {
+        request
+            .headers()
+            .iter()
+            .find(|h| h.field.equiv(name))
+            .map(|h| h.value.as_str())
+    };
+    let host = header("Host").unwrap_or_default();
+    if addr.ip().is_loopback() && !is_loopback_host(host) {
+        return Err(format!("Unexpected host: {}", host));
+    }
+    match header("Origin") {
+        Some(origin) if *request.method() != Method::Get => {
+            if origin.strip_prefix("http://") == Some(host) {
+                Ok(())
+            } else {
+                Err(format!("Unexpected origin: {}", origin))
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Whether `host`, the value of a Host header, is a loopback address or
+/// localhost, with or without a port.
+fn is_loopback_host(host: &str) -> bool {
+    let name = match host.rsplit_once(':') {
+        Some((name, port)) if !port.contains(']') => name,
+        _ => host,
+    };
+    let name = name.trim_start_matches('[').trim_end_matches(']');
+    name.eq_ignore_ascii_case("localhost")
+        || name.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+fn with_type<R: std::io::Read>(response: Response<R>, content_type: &str) -> Response<R> {
+    let header = Header::from_bytes("Content-Type", format!("{}; charset=utf-8", content_type))
+        .expect("valid header");
+    response.with_header(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loopback_hosts() {
+        for host in [
+            "localhost",
+            "localhost:7878",
+            "LocalHost:80",
+            "127.0.0.1",
+            "127.0.0.1:7878",
+            "127.1.2.3:7878",
+            "[::1]",
+            "[::1]:7878",
+        ] {
+            assert!(is_loopback_host(host), "{}", host);
+        }
+    }
+
+    #[test]
+    fn other_hosts() {
+        for host in [
+            "",
+            "evil.example:7878",
+            "localhost.evil.example:7878",
+            "192.168.1.2:7878",
+            "[::2]:7878",
+            "127.0.0.1.evil.example",
+        ] {
+            assert!(!is_loopback_host(host), "{}", host);
+        }
+    }
+}
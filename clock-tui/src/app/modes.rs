@@ -1,36 +1,179 @@
+//! The widgets drawn by the modes of tclock. They can also be embedded in
+//! other ratatui apps, see [`crate::widgets`].
+
 mod clock;
 mod countdown;
 mod pause;
+mod status;
 mod stopwatch;
 mod timer;
 
-use std::cmp::min;
+use std::cmp::{max, min};
 use std::fmt::Write as _;
 
+use crate::app::theme::Theme;
+use crate::clock_text::color::{ColorSpec, TextColors};
+use crate::clock_text::font::effect::Effect;
+use crate::clock_text::font::{Font, FontKind};
+use crate::clock_text::transition::{Transition, TransitionState};
 use crate::clock_text::ClockText;
-use chrono::Duration;
-pub(crate) use clock::Clock;
-pub(crate) use countdown::Countdown;
-pub(crate) use pause::Pause;
+use chrono::{DateTime, Duration};
+pub use clock::{ClockWidget, ClockWidgetBuilder};
+pub use countdown::{CountdownWidget, CountdownWidgetBuilder};
+pub use pause::Pause;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::Style,
+    style::{Color, Style},
+    symbols,
     text::Span,
-    widgets::{Paragraph, Widget},
+    widgets::{LineGauge, Paragraph, Widget},
 };
-pub(crate) use stopwatch::Stopwatch;
-pub(crate) use timer::Timer;
+pub use status::{RunState, Status, DEFAULT_STATUS_TEMPLATE};
+pub use stopwatch::{StopwatchState, StopwatchWidget, StopwatchWidgetBuilder};
+pub(crate) use timer::{parse_end_time_format, DEFAULT_END_TIME_FORMAT};
+pub use timer::{TimerState, TimerWidget, TimerWidgetBuilder};
 
-#[derive(Copy, Clone)]
-pub(crate) enum DurationFormat {
+/// How durations are shown by timers, stopwatches and countdowns.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DurationFormat {
     /// Hours, minutes, seconds, deciseconds
     HourMinSecDeci,
     /// Hours, minutes, seconds
     HourMinSec,
 }
 
-fn format_duration(duration: Duration, format: DurationFormat) -> String {
+/// Longest time a timer or stopwatch runs, so that the times computed from
+/// it, like when a timer ends, stay in the range of dates.
+pub const MAX_DURATION: Duration = Duration::days(100 * 365);
+
+/// How the digits of a widget are drawn, set with the builders of all widgets.
+#[derive(Debug, Clone)]
+pub(crate) struct Look {
+    pub font: FontKind,
+    pub size: f32,
+    pub effect: Option<Effect>,
+    pub transition: Option<Transition>,
+    pub style: Style,
+    /// `None` takes the color of `style`, or else the theme foreground
+    pub colors: Option<TextColors>,
+    pub theme: Theme,
+}
+
+impl Default for Look {
+    fn default() -> Self {
+        Self {
+            font: FontKind::Bricks,
+            size: 1.0,
+            effect: None,
+            transition: None,
+            style: Style::default(),
+            colors: None,
+            theme: Theme::default(),
+        }
+    }
+}
+
+/// What a widget draws with, built from a `Look`.
+pub(crate) struct Drawing {
+    pub font: Box<dyn Font>,
+    pub style: Style,
+    pub colors: TextColors,
+    pub theme: Theme,
+    pub transition: Option<TransitionState>,
+}
+
+impl Look {
+    fn build(self) -> Drawing {
+        let font = self.font.build(self.size);
+        let font = match self.effect {
+            Some(effect) => effect.apply(font),
+            None => font,
+        };
+        let colors = self.colors.unwrap_or_else(|| {
+            TextColors::new(
+                self.style
+                    .fg
+                    .map(ColorSpec::Solid)
+                    .unwrap_or_else(|| self.theme.foreground.clone()),
+            )
+        });
+        Drawing {
+            font,
+            // the digits are recolored as a rainbow cycles
+            style: self
+                .style
+                .fg(colors.base.color_at(0.0, DateTime::default())),
+            colors,
+            theme: self.theme,
+            transition: self.transition.map(TransitionState::new),
+        }
+    }
+}
+
+/// Setters of the `Look` of a widget builder.
+macro_rules! look_setters {
+    () => {
+        /// Font of the digits, bricks by default.
+        pub fn font(mut self, font: crate::clock_text::font::FontKind) -> Self {
+            self.look.font = font;
+            self
+        }
+
+        /// Size of the digits, at least 1 and 1 by default. Fractional sizes are honored by
+        /// the braille and half-block fonts, bricks fonts round to an integer.
+        pub fn size(mut self, size: f32) -> Self {
+            self.look.size = size;
+            self
+        }
+
+        /// Effect drawn on the digits.
+        pub fn effect(mut self, effect: crate::clock_text::font::effect::Effect) -> Self {
+            self.look.effect = Some(effect);
+            self
+        }
+
+        /// Animation of the digits when they change.
+        pub fn transition(mut self, transition: crate::clock_text::transition::Transition) -> Self {
+            self.look.transition = Some(transition);
+            self
+        }
+
+        /// Style of the digits, its foreground is the color of the digits
+        /// unless `color` or `colors` is set.
+        pub fn style(mut self, style: ratatui::style::Style) -> Self {
+            self.look.style = style;
+            self
+        }
+
+        /// Color of the digits, a solid color, a gradient or a rainbow.
+        pub fn color(mut self, color: crate::clock_text::color::ColorSpec) -> Self {
+            self.look.colors = Some(crate::clock_text::color::TextColors::new(color));
+            self
+        }
+
+        /// Colors of the hours, minutes, seconds and separators.
+        pub fn colors(mut self, colors: crate::clock_text::color::TextColors) -> Self {
+            self.look.colors = Some(colors);
+            self
+        }
+
+        /// Theme of the background, header, footer and messages.
+        pub fn theme(mut self, theme: crate::app::theme::Theme) -> Self {
+            self.look.theme = theme;
+            self
+        }
+
+        pub(crate) fn look(mut self, look: super::Look) -> Self {
+            self.look = look;
+            self
+        }
+    };
+}
+use look_setters;
+
+/// Formats `duration` like the widgets do, eg. "1:05:09.3" or "-4:59".
+pub fn format_duration(duration: Duration, format: DurationFormat) -> String {
     let is_neg = duration < Duration::zero();
     let duration = if is_neg { -duration } else { duration };
 
@@ -74,25 +217,115 @@ fn format_duration(duration: Duration, format: DurationFormat) -> String {
     result
 }
 
+/// Color the digits of a timer or countdown take once the remaining time is
+/// at most `remaining`.
+#[derive(Debug, Clone)]
+pub struct Threshold {
+    pub remaining: Duration,
+    pub color: ColorSpec,
+    /// Alternate between `color` and the normal colors every half second
+    pub blink: bool,
+}
+
+/// Colors of the closest threshold reached with `remaining` time left, or
+/// `None` to keep the normal colors.
+fn threshold_colors(thresholds: &[Threshold], remaining: Duration) -> Option<TextColors> {
+    let threshold = thresholds
+        .iter()
+        .filter(|t| remaining <= t.remaining)
+        .min_by_key(|t| t.remaining)?;
+    let blink_off = threshold.blink && remaining.num_milliseconds().rem_euclid(1000) >= 500;
+    (!blink_off).then(|| TextColors::new(threshold.color.clone()))
+}
+
+/// Whether `remaining` has reached a threshold, for the warning state of a
+/// status.
+fn is_running_low(thresholds: &[Threshold], remaining: Duration) -> bool {
+    remaining >= Duration::zero() && thresholds.iter().any(|t| remaining <= t.remaining)
+}
+
+/// A progress bar drawn under the digits.
+pub(crate) struct Progress {
+    /// Done part, from 0.0 to 1.0
+    pub ratio: f64,
+    pub label: String,
+}
+
+impl Progress {
+    /// Progress of `done` out of `total`, labeled with `title` and the percentage.
+    fn new(title: &str, done: Duration, total: Duration) -> Self {
+        let ratio = if total > Duration::zero() {
+            done.num_milliseconds() as f64 / total.num_milliseconds() as f64
+        } else {
+            1.0
+        };
+        let ratio = ratio.clamp(0.0, 1.0);
+        let label = format!("{}{:.0}%", title, ratio * 100.0);
+        Self { ratio, label }
+    }
+
+    /// The done part from 0 to 100, for the percentage of a status.
+    fn percentage(&self) -> u8 {
+        (self.ratio * 100.0).round() as u8
+    }
+}
+
+/// Minimum width of the progress bars, so they stay usable under small fonts.
+const MIN_PROGRESS_WIDTH: u16 = 20;
+
+/// Fills `area` with the theme background and draws `text` in the middle,
+/// `header` above it, and the `progress` bars and then the `footer` lines below it.
 fn render_centered(
     area: Rect,
     buf: &mut Buffer,
     text: &ClockText,
+    theme: &Theme,
     header: Option<String>,
-    footer: Option<String>,
+    footer: Vec<Span>,
+    progress: &[Progress],
 ) {
+    theme.fill_background(area, buf);
     let text_size = text.size();
+    // The progress bars take a row each, after a blank one
+    let progress_height = if progress.is_empty() {
+        0
+    } else {
+        progress.len() as u16 + 1
+    };
     let text_area = Rect {
         x: area.x + (area.width.saturating_sub(text_size.0)) / 2,
-        y: area.y + (area.height.saturating_sub(text_size.1)) / 2,
+        y: area.y + (area.height.saturating_sub(text_size.1 + progress_height)) / 2,
         width: min(text_size.0, area.width),
         height: min(text_size.1, area.height),
     };
     text.clone().render(text_area, buf);
 
-    let render_text_center = |text: &str, top: u16, buf: &mut Buffer| {
-        let text_len = text.len() as u16;
-        let paragrahp = Paragraph::new(Span::from(text)).style(Style::default());
+    let progress_width = min(max(text_area.width, MIN_PROGRESS_WIDTH), area.width);
+    let gauge_style = Style::default()
+        .fg(text.style.fg.unwrap_or(Color::Reset))
+        .bg(Color::DarkGray);
+    for (i, progress) in progress.iter().enumerate() {
+        let top = text_area.bottom() + 1 + i as u16;
+        if top >= area.bottom() {
+            break;
+        }
+        let gauge_area = Rect {
+            x: area.x + (area.width - progress_width) / 2,
+            y: top,
+            width: progress_width,
+            height: 1,
+        };
+        LineGauge::default()
+            .ratio(progress.ratio)
+            .label(Span::styled(progress.label.clone(), theme.footer))
+            .line_set(symbols::line::THICK)
+            .gauge_style(gauge_style)
+            .render(gauge_area, buf);
+    }
+
+    let render_text_center = |text: Span, top: u16, buf: &mut Buffer| {
+        let text_len = text.width() as u16;
+        let paragrahp = Paragraph::new(text);
 
         let para_area = Rect {
             x: area.left() + (area.width.saturating_sub(text_len)) / 2,
@@ -105,13 +338,15 @@ fn render_centered(
 
     if let Some(text) = header {
         if area.top() + 2 <= text_area.top() {
-            render_text_center(text.as_str(), text_area.top() - 2, buf);
+            render_text_center(Span::styled(text, theme.header), text_area.top() - 2, buf);
         }
     }
 
-    if let Some(text) = footer {
-        if area.bottom() >= text_area.bottom() + 2 {
-            render_text_center(text.as_str(), text_area.bottom() + 1, buf);
+    for (i, text) in footer.into_iter().enumerate() {
+        let top = text_area.bottom() + progress_height + 1 + i as u16;
+        if area.bottom() <= top {
+            break;
         }
+        render_text_center(text, top, buf);
     }
 }
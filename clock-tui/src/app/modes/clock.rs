@@ -1,26 +1,113 @@
-use crate::clock_text::font::bricks::BricksFont;
+use std::rc::Rc;
+
+use crate::app::theme::Theme;
+use crate::clock_text::color::TextColors;
+use crate::clock_text::font::Font;
+use crate::clock_text::transition::TransitionState;
 use crate::clock_text::ClockText;
-use chrono::{Local, Utc};
+use crate::time_source::{SystemClock, TimeSource};
 use chrono_tz::Tz;
 use ratatui::{buffer::Buffer, layout::Rect, style::Style, widgets::Widget};
 
-use super::render_centered;
+use super::{look_setters, render_centered, Look, RunState, Status};
 
-pub(crate) struct Clock {
-    pub size: u16,
-    pub style: Style,
-    pub show_date: bool,
-    pub show_millis: bool,
-    pub show_secs: bool,
-    pub timezone: Option<Tz>,
+/// A clock showing the current time.
+pub struct ClockWidget {
+    font: Box<dyn Font>,
+    style: Style,
+    colors: TextColors,
+    theme: Theme,
+    pub(crate) transition: Option<TransitionState>,
+    show_date: bool,
+    show_millis: bool,
+    show_secs: bool,
+    timezone: Option<Tz>,
+    time_source: Rc<dyn TimeSource>,
 }
 
-impl Widget for &Clock {
-    fn render(self, area: Rect, buf: &mut Buffer) {
+/// Builds a [`ClockWidget`], by default showing the date and seconds in the
+/// local timezone.
+pub struct ClockWidgetBuilder {
+    look: Look,
+    show_date: bool,
+    show_millis: bool,
+    show_secs: bool,
+    timezone: Option<Tz>,
+    time_source: Option<Rc<dyn TimeSource>>,
+}
+
+impl ClockWidgetBuilder {
+    look_setters!();
+
+    /// Shows the date above the time.
+    pub fn show_date(mut self, show: bool) -> Self {
+        self.show_date = show;
+        self
+    }
+
+    pub fn show_seconds(mut self, show: bool) -> Self {
+        self.show_secs = show;
+        self
+    }
+
+    /// Shows milliseconds, and seconds with them.
+    pub fn show_millis(mut self, show: bool) -> Self {
+        self.show_millis = show;
+        self
+    }
+
+    /// Timezone of the time, the local one by default.
+    pub fn timezone(mut self, timezone: Option<Tz>) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Where the current time comes from, the system time by default.
+    pub fn time_source(mut self, time_source: Rc<dyn TimeSource>) -> Self {
+        self.time_source = Some(time_source);
+        self
+    }
+
+    pub fn build(self) -> ClockWidget {
+        let drawing = self.look.build();
+        ClockWidget {
+            font: drawing.font,
+            style: drawing.style,
+            colors: drawing.colors,
+            theme: drawing.theme,
+            transition: drawing.transition,
+            show_date: self.show_date,
+            show_millis: self.show_millis,
+            show_secs: self.show_secs,
+            timezone: self.timezone,
+            time_source: self
+                .time_source
+                .unwrap_or_else(|| Rc::new(SystemClock::default())),
+        }
+    }
+}
+
+impl ClockWidget {
+    pub fn builder() -> ClockWidgetBuilder {
+        ClockWidgetBuilder {
+            look: Look::default(),
+            show_date: true,
+            show_millis: false,
+            show_secs: true,
+            timezone: None,
+            time_source: None,
+        }
+    }
+}
+
+impl ClockWidget {
+    /// The time and the date shown, with the timezone if it is not the local one.
+    fn time_and_date(&self) -> (String, String) {
+        let now = self.time_source.now();
         let now = if let Some(ref tz) = self.timezone {
-            Utc::now().with_timezone(tz).naive_local()
+            now.with_timezone(tz).naive_local()
         } else {
-            Local::now().naive_local()
+            now.naive_local()
         };
         let mut time_str = now.format("%H:%M:%S%.3f").to_string();
         if self.show_millis {
@@ -30,19 +117,91 @@ impl Widget for &Clock {
         } else {
             time_str.truncate(time_str.len() - 4);
         }
-        let time_str = time_str.as_str();
-        let font = BricksFont::new(self.size);
-        let text = ClockText::new(time_str.to_string(), &font, self.style);
-        let header = if self.show_date {
-            let mut title = now.format("%Y-%m-%d").to_string();
-            if let Some(tz) = self.timezone {
-                title.push(' ');
-                title.push_str(tz.name());
-            }
-            Some(title)
-        } else {
-            None
-        };
-        render_centered(area, buf, &text, header, None);
+        let mut date = now.format("%Y-%m-%d").to_string();
+        if let Some(tz) = self.timezone {
+            date.push(' ');
+            date.push_str(tz.name());
+        }
+        (time_str, date)
+    }
+
+    /// The time and date of the clock.
+    pub fn status(&self) -> Status {
+        let (time, date) = self.time_and_date();
+        Status {
+            title: None,
+            remaining: None,
+            elapsed: None,
+            time: Some(time),
+            date: Some(date.clone()),
+            tooltip: Some(date),
+            segment: None,
+            percentage: None,
+            state: RunState::Running,
+        }
+    }
+}
+
+impl Widget for &ClockWidget {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (time_str, date) = self.time_and_date();
+        let text = ClockText::new(time_str, &*self.font, self.style)
+            .with_colors(&self.colors, self.show_secs || self.show_millis)
+            .with_transition(self.transition.as_ref())
+            .with_time_source(&*self.time_source);
+        let header = self.show_date.then_some(date);
+        render_centered(area, buf, &text, &self.theme, header, Vec::new(), &[]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Local, TimeZone};
+    use ratatui::{backend::TestBackend, Terminal};
+
+    use super::*;
+    use crate::clock_text::color::ColorSpec;
+    use crate::clock_text::transition::Transition;
+    use crate::time_source::ManualClock;
+
+    fn render(widget: &ClockWidget) -> Buffer {
+        let mut terminal = Terminal::new(TestBackend::new(60, 9)).unwrap();
+        terminal
+            .draw(|f| f.render_widget(widget, f.size()))
+            .unwrap();
+        terminal.backend().buffer().clone()
+    }
+
+    fn text(buf: &Buffer) -> String {
+        buf.content.iter().map(|cell| cell.symbol()).collect()
+    }
+
+    #[test]
+    fn renders_the_time_of_the_time_source() {
+        let now = Local.with_ymd_and_hms(2025, 1, 1, 12, 34, 56).unwrap();
+        let clock = Rc::new(ManualClock::new(now));
+        let widget = ClockWidget::builder()
+            .color(ColorSpec::Rainbow)
+            .transition(Transition::Flip)
+            .time_source(clock.clone())
+            .build();
+
+        let frame = render(&widget);
+        assert!(text(&frame).contains("2025-01-01"));
+        assert_eq!(widget.status().time.as_deref(), Some("12:34:56"));
+        // nothing moves while the clock stands still, the rainbow included
+        assert_eq!(render(&widget), frame);
+
+        clock.advance(std::time::Duration::from_secs(1));
+        let changed = render(&widget);
+        assert!(widget.transition.as_ref().unwrap().is_animating());
+        assert_eq!(render(&widget), changed);
+
+        // the flip is over before the next second, and the rainbow has moved on
+        clock.advance(std::time::Duration::from_millis(900));
+        let next = render(&widget);
+        assert!(!widget.transition.as_ref().unwrap().is_animating());
+        assert_eq!(widget.status().time.as_deref(), Some("12:34:57"));
+        assert_ne!(next, changed);
     }
 }
@@ -1,23 +1,152 @@
-use crate::clock_text::font::bricks::BricksFont;
+use std::rc::Rc;
+
+use crate::app::theme::Theme;
+use crate::clock_text::color::TextColors;
+use crate::clock_text::font::Font;
+use crate::clock_text::transition::TransitionState;
 use crate::clock_text::ClockText;
+use crate::time_source::{SystemClock, TimeSource};
 use chrono::{DateTime, Duration, Local};
 use ratatui::{style::Style, widgets::Widget};
 
-use super::{format_duration, render_centered, DurationFormat};
+use super::{
+    format_duration, is_running_low, look_setters, render_centered, threshold_colors,
+    DurationFormat, Look, Progress, RunState, Status, Threshold,
+};
+
+/// A countdown to a given time, or a count up from it.
+pub struct CountdownWidget {
+    font: Box<dyn Font>,
+    style: Style,
+    colors: TextColors,
+    theme: Theme,
+    pub(crate) transition: Option<TransitionState>,
+    time: DateTime<Local>,
+    title: Option<String>,
+    continue_on_zero: bool,
+    reverse: bool,
+    format: DurationFormat,
+    thresholds: Vec<Threshold>,
+    /// Show a progress bar measured from this time
+    progress_from: Option<DateTime<Local>>,
+    time_source: Rc<dyn TimeSource>,
+}
 
-pub struct Countdown {
-    pub size: u16,
-    pub style: Style,
-    pub time: DateTime<Local>,
-    pub title: Option<String>,
-    pub continue_on_zero: bool,
-    pub(crate) reverse: bool,
-    pub(crate) format: DurationFormat,
+/// Builds a [`CountdownWidget`], by default counting down to the time it is
+/// built at.
+pub struct CountdownWidgetBuilder {
+    look: Look,
+    time: Option<DateTime<Local>>,
+    title: Option<String>,
+    continue_on_zero: bool,
+    reverse: bool,
+    format: DurationFormat,
+    thresholds: Vec<Threshold>,
+    show_progress: bool,
+    progress_from: Option<DateTime<Local>>,
+    time_source: Option<Rc<dyn TimeSource>>,
 }
 
-impl Countdown {
-    pub(crate) fn remaining_time(&self) -> Duration {
-        let now = Local::now();
+impl CountdownWidgetBuilder {
+    look_setters!();
+
+    /// The time to count down to.
+    pub fn time(mut self, time: DateTime<Local>) -> Self {
+        self.time = Some(time);
+        self
+    }
+
+    /// Title shown above the digits.
+    pub fn title(mut self, title: Option<String>) -> Self {
+        self.title = title;
+        self
+    }
+
+    /// Keeps counting, below zero, once the time is reached.
+    pub fn continue_on_zero(mut self, continue_on_zero: bool) -> Self {
+        self.continue_on_zero = continue_on_zero;
+        self
+    }
+
+    /// Counts up from the time instead.
+    pub fn reverse(mut self, reverse: bool) -> Self {
+        self.reverse = reverse;
+        self
+    }
+
+    /// Format of the remaining time, without deciseconds by default.
+    pub fn format(mut self, format: DurationFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Colors of the digits as the remaining time runs low.
+    pub fn thresholds(mut self, thresholds: Vec<Threshold>) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Shows a progress bar of the time passed since the widget was built.
+    pub fn show_progress(mut self, show: bool) -> Self {
+        self.show_progress = show;
+        self
+    }
+
+    /// Shows a progress bar of the time passed since `from`.
+    pub fn progress_from(mut self, from: Option<DateTime<Local>>) -> Self {
+        self.progress_from = from;
+        self
+    }
+
+    /// Where the current time comes from, the system time by default.
+    pub fn time_source(mut self, time_source: Rc<dyn TimeSource>) -> Self {
+        self.time_source = Some(time_source);
+        self
+    }
+
+    pub fn build(self) -> CountdownWidget {
+        let drawing = self.look.build();
+        let time_source = self
+            .time_source
+            .unwrap_or_else(|| Rc::new(SystemClock::default()));
+        let now = time_source.now();
+        CountdownWidget {
+            font: drawing.font,
+            style: drawing.style,
+            colors: drawing.colors,
+            theme: drawing.theme,
+            transition: drawing.transition,
+            time: self.time.unwrap_or(now),
+            title: self.title,
+            continue_on_zero: self.continue_on_zero,
+            reverse: self.reverse,
+            format: self.format,
+            thresholds: self.thresholds,
+            progress_from: self.progress_from.or(self.show_progress.then_some(now)),
+            time_source,
+        }
+    }
+}
+
+impl CountdownWidget {
+    pub fn builder() -> CountdownWidgetBuilder {
+        CountdownWidgetBuilder {
+            look: Look::default(),
+            time: None,
+            title: None,
+            continue_on_zero: false,
+            reverse: false,
+            format: DurationFormat::HourMinSec,
+            thresholds: Vec::new(),
+            show_progress: false,
+            progress_from: None,
+            time_source: None,
+        }
+    }
+
+    /// The time left until the target time, or passed since it when counting up.
+    pub fn remaining_time(&self) -> Duration {
+        let now = self.time_source.now();
         let result = self.time.signed_duration_since(now);
         if self.reverse {
             -result
@@ -27,11 +156,58 @@ impl Countdown {
     }
 }
 
-impl Widget for &Countdown {
+impl CountdownWidget {
+    /// Whether the time is reached, and the countdown stops at zero.
+    fn is_over(&self, remaining: Duration) -> bool {
+        remaining < Duration::zero() && !self.continue_on_zero
+    }
+
+    /// The title, remaining time and state of the countdown, with the target
+    /// time as tooltip.
+    pub fn status(&self) -> Status {
+        let remaining = self.remaining_time();
+        let over = self.is_over(remaining);
+        let state = if over && !self.reverse {
+            RunState::Finished
+        } else if !self.reverse && is_running_low(&self.thresholds, remaining) {
+            RunState::Warning
+        } else {
+            RunState::Running
+        };
+        Status {
+            title: self.title.clone(),
+            remaining: Some(format_duration(
+                if over { Duration::zero() } else { remaining },
+                self.format,
+            )),
+            elapsed: None,
+            time: None,
+            date: None,
+            tooltip: Some(self.time.format("%Y-%m-%d %H:%M:%S").to_string()),
+            segment: None,
+            percentage: self.progress(remaining).first().map(Progress::percentage),
+            state,
+        }
+    }
+
+    /// The progress bar, if any, with `remaining` time left.
+    fn progress(&self, remaining: Duration) -> Vec<Progress> {
+        match self.progress_from {
+            Some(from) if !self.reverse => {
+                let total = self.time.signed_duration_since(from);
+                vec![Progress::new("", total - remaining, total)]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl Widget for &CountdownWidget {
     fn render(self, area: ratatui::layout::Rect, buf: &mut ratatui::buffer::Buffer) {
         let remaining_time = self.remaining_time();
-        let time_str = if remaining_time < Duration::zero() && !self.continue_on_zero {
+        let time_str = if self.is_over(remaining_time) {
             if (remaining_time.num_milliseconds()).abs() % 1000 < 500 {
+                self.theme.fill_background(area, buf);
                 return;
             } else {
                 format_duration(Duration::zero(), self.format)
@@ -40,8 +216,23 @@ impl Widget for &Countdown {
             format_duration(remaining_time, self.format)
         };
 
-        let font = BricksFont::new(self.size);
-        let text = ClockText::new(time_str.to_string(), &font, self.style);
-        render_centered(area, buf, &text, self.title.to_owned(), None);
+        // Counting up there is no time running low
+        let colors = (!self.reverse)
+            .then(|| threshold_colors(&self.thresholds, remaining_time))
+            .flatten();
+        let text = ClockText::new(time_str.to_string(), &*self.font, self.style)
+            .with_colors(colors.as_ref().unwrap_or(&self.colors), true)
+            .with_transition(self.transition.as_ref())
+            .with_time_source(&*self.time_source);
+        let progress = self.progress(remaining_time);
+        render_centered(
+            area,
+            buf,
+            &text,
+            &self.theme,
+            self.title.to_owned(),
+            Vec::new(),
+            &progress,
+        );
     }
 }
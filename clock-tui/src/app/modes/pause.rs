@@ -1,12 +1,13 @@
-/// Trait for implementing pause/resume functionality
+/// Pausing and resuming something that runs with time, like the state of a
+/// timer or stopwatch widget. Time passed while paused does not count.
 pub trait Pause {
-    /// Returns true if the widget is currently paused
+    /// Returns true if it is currently paused
     fn is_paused(&self) -> bool;
 
-    /// Pauses the widget
+    /// Pauses it, does nothing if it is already paused
     fn pause(&mut self);
 
-    /// Resumes the widget
+    /// Resumes it, does nothing if it is not paused
     fn resume(&mut self);
 
     /// Toggles the pause state
@@ -0,0 +1,140 @@
+use regex::{Captures, Regex};
+use serde::Serialize;
+
+/// Whether a widget is running, for status lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunState {
+    Running,
+    Paused,
+    /// A timer or a countdown is running low, see `Threshold`
+    Warning,
+    /// A timer or a countdown is over
+    Finished,
+}
+
+impl RunState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RunState::Running => "running",
+            RunState::Paused => "paused",
+            RunState::Warning => "warning",
+            RunState::Finished => "finished",
+        }
+    }
+}
+
+/// What a widget shows as text, eg. to print it in a status bar. Fields the
+/// widget does not show are empty.
+#[derive(Debug, Clone, Serialize)]
+pub struct Status {
+    /// Title of a timer duration or of a countdown
+    pub title: Option<String>,
+    /// Remaining time of a timer or countdown, negative once a timer is over
+    pub remaining: Option<String>,
+    /// Time a stopwatch has been running
+    pub elapsed: Option<String>,
+    /// Time of a clock
+    pub time: Option<String>,
+    /// Date of a clock
+    pub date: Option<String>,
+    /// More details, like the durations of a timer and when they end
+    pub tooltip: Option<String>,
+    /// Index of the current duration of a timer, from 0
+    pub segment: Option<usize>,
+    /// Progress of a timer duration or of a countdown, from 0 to 100
+    pub percentage: Option<u8>,
+    pub state: RunState,
+}
+
+/// Default template of `Status::format`.
+pub const DEFAULT_STATUS_TEMPLATE: &str = "{title} {remaining}{elapsed}{time}";
+
+impl Status {
+    /// Replaces `{title}`, `{remaining}`, `{elapsed}`, `{time}`, `{date}` and
+    /// `{state}` in `template` with the fields of the status, and trims the
+    /// blanks left at the ends by empty fields. Times are in whole seconds, so
+    /// that the line changes once a second at most, unless asked for with
+    /// `{remaining:millis}`, `{elapsed:millis}` or `{time:millis}`.
+    pub fn format(&self, template: &str) -> String {
+        let re = Regex::new(r"\{(title|remaining|elapsed|time|date|state)(:millis)?\}").unwrap();
+        let line = re.replace_all(template, |cap: &Captures| {
+            let is_time = matches!(&cap[1], "remaining" | "elapsed" | "time");
+            let millis = cap.get(2).is_some();
+            if millis && !is_time {
+                return cap[0].to_string();
+            }
+            let field = match &cap[1] {
+                "title" => &self.title,
+                "remaining" => &self.remaining,
+                "elapsed" => &self.elapsed,
+                "time" => &self.time,
+                "date" => &self.date,
+                _ => return self.state.name().to_string(),
+            };
+            let field = field.as_deref().unwrap_or_default();
+            if is_time && !millis {
+                whole_seconds(field).to_string()
+            } else {
+                field.to_string()
+            }
+        });
+        line.trim().to_string()
+    }
+}
+
+/// Drops the fraction of a second of a time like "4:59.3".
+fn whole_seconds(time: &str) -> &str {
+    time.split('.').next().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status() -> Status {
+        Status {
+            title: Some("Work".to_string()),
+            remaining: Some("24:59.3".to_string()),
+            elapsed: None,
+            time: None,
+            date: None,
+            tooltip: None,
+            segment: Some(0),
+            percentage: Some(1),
+            state: RunState::Running,
+        }
+    }
+
+    #[test]
+    fn format_whole_seconds() {
+        assert_eq!(status().format(DEFAULT_STATUS_TEMPLATE), "Work 24:59");
+        assert_eq!(status().format("{remaining} ({state})"), "24:59 (running)");
+    }
+
+    #[test]
+    fn format_millis() {
+        assert_eq!(
+            status().format("{title} {remaining:millis}"),
+            "Work 24:59.3"
+        );
+        assert_eq!(status().format("{title:millis}"), "{title:millis}");
+        // only if the mode shows them
+        let status = Status {
+            remaining: Some("24:59".to_string()),
+            ..status()
+        };
+        assert_eq!(status.format("{remaining:millis}"), "24:59");
+    }
+
+    #[test]
+    fn format_empty_fields() {
+        let status = Status {
+            title: None,
+            ..status()
+        };
+        assert_eq!(status.format(DEFAULT_STATUS_TEMPLATE), "24:59");
+        assert_eq!(status.format("{date} {elapsed}"), "");
+        assert_eq!(status.format("{nosuch}"), "{nosuch}");
+    }
+}
@@ -1,73 +1,221 @@
-use crate::clock_text::font::bricks::BricksFont;
+use std::rc::Rc;
+use std::time::Instant;
+
+use crate::app::theme::Theme;
+use crate::clock_text::color::TextColors;
+use crate::clock_text::font::Font;
+use crate::clock_text::transition::TransitionState;
 use crate::clock_text::ClockText;
-use chrono::{DateTime, Duration, Local};
-use ratatui::{buffer::Buffer, layout::Rect, style::Style, widgets::Widget};
+use crate::session::{SessionKind, SessionState};
+use crate::time_source::{SystemClock, TimeSource};
+use chrono::Duration;
+use ratatui::{buffer::Buffer, layout::Rect, style::Style, text::Span, widgets::StatefulWidget};
 
 use crate::app::modes::pause::Pause;
 
-use super::{format_duration, render_centered, DurationFormat};
+use super::{
+    format_duration, look_setters, render_centered, DurationFormat, Look, RunState, Status,
+    MAX_DURATION,
+};
+
+/// A stopwatch, rendered with the elapsed time kept in a [`StopwatchState`].
+pub struct StopwatchWidget {
+    font: Box<dyn Font>,
+    style: Style,
+    colors: TextColors,
+    theme: Theme,
+    pub(crate) transition: Option<TransitionState>,
+}
+
+/// Builds a [`StopwatchWidget`].
+pub struct StopwatchWidgetBuilder {
+    look: Look,
+}
+
+impl StopwatchWidgetBuilder {
+    look_setters!();
+
+    pub fn build(self) -> StopwatchWidget {
+        let drawing = self.look.build();
+        StopwatchWidget {
+            font: drawing.font,
+            style: drawing.style,
+            colors: drawing.colors,
+            theme: drawing.theme,
+            transition: drawing.transition,
+        }
+    }
+}
+
+impl StopwatchWidget {
+    pub fn builder() -> StopwatchWidgetBuilder {
+        StopwatchWidgetBuilder {
+            look: Look::default(),
+        }
+    }
 
-pub struct Stopwatch {
-    pub size: u16,
-    pub style: Style,
+    /// The elapsed time and state of the stopwatch, with the laps as tooltip.
+    pub fn status(&self, state: &StopwatchState) -> Status {
+        Status {
+            title: None,
+            remaining: None,
+            elapsed: Some(format_duration(
+                state.elapsed(),
+                DurationFormat::HourMinSecDeci,
+            )),
+            time: None,
+            date: None,
+            tooltip: (!state.laps.is_empty()).then(|| {
+                state
+                    .laps
+                    .iter()
+                    .enumerate()
+                    .map(|(i, lap)| {
+                        format!(
+                            "Lap {}: {}",
+                            i + 1,
+                            format_duration(*lap, DurationFormat::HourMinSecDeci)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }),
+            segment: None,
+            percentage: None,
+            state: if state.is_paused() {
+                RunState::Paused
+            } else {
+                RunState::Running
+            },
+        }
+    }
+}
+
+/// The time a [`StopwatchWidget`] has been running, paused and resumed with
+/// the [`Pause`] trait.
+pub struct StopwatchState {
+    time_source: Rc<dyn TimeSource>,
     duration: Duration,
-    started_at: Option<DateTime<Local>>,
+    started_at: Option<Instant>,
+    laps: Vec<Duration>,
+}
+
+impl Default for StopwatchState {
+    fn default() -> Self {
+        Self::new(Rc::new(SystemClock::default()))
+    }
 }
 
-impl Stopwatch {
-    pub(crate) fn new(size: u16, style: Style) -> Self {
+impl StopwatchState {
+    /// A stopwatch running from now on, as told by `time_source`.
+    pub fn new(time_source: Rc<dyn TimeSource>) -> Self {
         Self {
-            size,
-            style,
             duration: Duration::zero(),
-            started_at: Some(Local::now()),
+            started_at: Some(time_source.instant()),
+            time_source,
+            laps: Vec::new(),
         }
     }
 
-    pub(crate) fn total_time(&self) -> Duration {
+    /// Time the stopwatch has been running, pauses excluded.
+    pub fn elapsed(&self) -> Duration {
         if let Some(start_at) = self.started_at {
-            let now = Local::now();
-            self.duration + now.signed_duration_since(start_at)
+            self.duration + self.time_source.elapsed(start_at)
         } else {
             self.duration
         }
     }
 
-    pub fn get_display_time(&self) -> String {
-        format_duration(self.total_time(), DurationFormat::HourMinSecDeci)
+    /// Adds `duration` to the elapsed time. Fails if it would be longer than
+    /// [`MAX_DURATION`].
+    pub fn add(&mut self, duration: Duration) -> Result<(), String> {
+        match self.elapsed().checked_add(&duration) {
+            Some(elapsed) if elapsed <= MAX_DURATION => {
+                self.duration += duration;
+                Ok(())
+            }
+            _ => Err("The stopwatch would run too long".to_string()),
+        }
+    }
+
+    /// Records the elapsed time as a lap.
+    pub fn lap(&mut self) {
+        self.laps.push(self.elapsed());
+    }
+
+    /// The elapsed times recorded with `lap`, oldest first.
+    pub fn laps(&self) -> &[Duration] {
+        &self.laps
+    }
+
+    /// Starts over from zero without laps, paused if it is paused.
+    pub fn reset(&mut self) {
+        self.duration = Duration::zero();
+        self.laps.clear();
+        if self.started_at.is_some() {
+            self.started_at = Some(self.time_source.instant());
+        }
+    }
+
+    pub(crate) fn session_state(&self) -> SessionState {
+        SessionState::new(
+            SessionKind::Stopwatch,
+            self.duration,
+            self.started_at,
+            &*self.time_source,
+        )
+    }
+
+    pub(crate) fn restore(&mut self, state: &SessionState) {
+        (self.duration, self.started_at) = state.restore(&*self.time_source);
     }
 }
 
-impl Widget for &Stopwatch {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        let time_str = format_duration(self.total_time(), DurationFormat::HourMinSecDeci);
-        let font = BricksFont::new(self.size);
-        let text = ClockText::new(time_str.to_string(), &font, self.style);
-        let footer = if self.is_paused() {
-            Some("PAUSED (press <SPACE> to resume)".to_string())
-        } else {
-            None
-        };
-        render_centered(area, buf, &text, None, footer);
+impl StatefulWidget for &StopwatchWidget {
+    type State = StopwatchState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut StopwatchState) {
+        let time_str = format_duration(state.elapsed(), DurationFormat::HourMinSecDeci);
+        let text = ClockText::new(time_str.to_string(), &*self.font, self.style)
+            .with_colors(&self.colors, true)
+            .with_transition(self.transition.as_ref())
+            .with_time_source(&*state.time_source);
+        let mut footer = Vec::new();
+        if let Some(lap) = state.laps.last() {
+            footer.push(Span::styled(
+                format!(
+                    "Lap {}: {}",
+                    state.laps.len(),
+                    format_duration(*lap, DurationFormat::HourMinSecDeci)
+                ),
+                self.theme.footer,
+            ));
+        }
+        if state.is_paused() {
+            footer.push(Span::styled(
+                "PAUSED (press <SPACE> to resume)",
+                self.theme.paused,
+            ));
+        }
+        render_centered(area, buf, &text, &self.theme, None, footer, &[]);
     }
 }
 
-impl Pause for Stopwatch {
+impl Pause for StopwatchState {
     fn is_paused(&self) -> bool {
         self.started_at.is_none()
     }
 
     fn pause(&mut self) {
         if let Some(start_at) = self.started_at {
-            let now = Local::now();
-            self.duration = self.duration + now.signed_duration_since(start_at);
+            self.duration += self.time_source.elapsed(start_at);
             self.started_at = None;
         }
     }
 
     fn resume(&mut self) {
         if self.started_at.is_none() {
-            self.started_at = Some(Local::now());
+            self.started_at = Some(self.time_source.instant());
         }
     }
 }
@@ -1,88 +1,539 @@
-use std::{cell::RefCell, cmp::min, process::Command};
+use std::fmt::Write as _;
+use std::rc::Rc;
+use std::time::Instant;
+use std::{cmp::min, process::Command};
 
 use crate::app::modes::pause::Pause;
-use crate::clock_text::font::bricks::BricksFont;
+use crate::app::theme::Theme;
+use crate::clock_text::color::TextColors;
+use crate::clock_text::font::Font;
+use crate::clock_text::transition::TransitionState;
 use crate::clock_text::ClockText;
+use crate::session::{SessionKind, SessionState};
+use crate::time_source::{SystemClock, TimeSource};
+use chrono::format::{Item, StrftimeItems};
 use chrono::{DateTime, Duration, Local};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::{Color, Style},
-    widgets::Widget,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, StatefulWidget, Widget},
 };
+use regex::{Captures, Regex};
 
-use super::{format_duration, render_centered, DurationFormat};
+use super::{
+    format_duration, is_running_low, look_setters, render_centered, threshold_colors,
+    DurationFormat, Look, Progress, RunState, Status, Threshold, MAX_DURATION,
+};
+
+/// A timer counting down a sequence of durations, rendered with the
+/// elapsed time kept in a [`TimerState`].
+pub struct TimerWidget {
+    font: Box<dyn Font>,
+    style: Style,
+    colors: TextColors,
+    theme: Theme,
+    pub(crate) transition: Option<TransitionState>,
+    repeat: bool,
+    durations: Vec<Duration>,
+    titles: Vec<String>,
+    execute: Vec<String>,
+    thresholds: Vec<Threshold>,
+    /// Show a progress bar for the current duration
+    show_progress: bool,
+    /// Show a progress bar for the whole sequence of durations
+    show_total_progress: bool,
+    /// Show the list of durations with their titles and end times
+    show_segments: bool,
+    /// Show when the timer ends in this format, see `format_end_time`
+    end_time_format: Option<String>,
+    auto_quit: bool,
+    format: DurationFormat,
+}
 
-pub struct Timer {
-    pub size: u16,
-    pub style: Style,
-    pub repeat: bool,
-    pub durations: Vec<Duration>,
-    pub titles: Vec<String>,
-    pub execute: Vec<String>,
+/// Builds a [`TimerWidget`], by default a single 5 minutes duration.
+pub struct TimerWidgetBuilder {
+    look: Look,
+    repeat: bool,
+    durations: Vec<Duration>,
+    titles: Vec<String>,
+    execute: Vec<String>,
+    thresholds: Vec<Threshold>,
+    show_progress: bool,
+    show_total_progress: bool,
+    show_segments: bool,
+    end_time_format: Option<String>,
     auto_quit: bool,
     format: DurationFormat,
+}
+
+impl TimerWidgetBuilder {
+    look_setters!();
+
+    /// Durations run one after another.
+    pub fn durations(mut self, durations: Vec<Duration>) -> Self {
+        self.durations = durations;
+        self
+    }
+
+    /// Titles of the durations shown above the digits, the last one is kept
+    /// for the durations without a title.
+    pub fn titles(mut self, titles: Vec<String>) -> Self {
+        self.titles = titles;
+        self
+    }
+
+    /// Starts over when the last duration is over.
+    pub fn repeat(mut self, repeat: bool) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// Format of the remaining time, with deciseconds by default.
+    pub fn format(mut self, format: DurationFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Shell command run when the last duration is over, its output is shown
+    /// under the digits.
+    pub fn execute(mut self, execute: Vec<String>) -> Self {
+        self.execute = execute;
+        self
+    }
+
+    /// Whether the timer is finished as soon as it is over, see
+    /// [`TimerWidget::is_finished`].
+    pub fn auto_quit(mut self, auto_quit: bool) -> Self {
+        self.auto_quit = auto_quit;
+        self
+    }
+
+    /// Colors of the digits as the remaining time runs low.
+    pub fn thresholds(mut self, thresholds: Vec<Threshold>) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Shows a progress bar for the current duration.
+    pub fn show_progress(mut self, show: bool) -> Self {
+        self.show_progress = show;
+        self
+    }
+
+    /// Shows a progress bar for the whole sequence of durations.
+    pub fn show_total_progress(mut self, show: bool) -> Self {
+        self.show_total_progress = show;
+        self
+    }
+
+    /// Lists the durations with their titles and the time each one ends.
+    pub fn show_segments(mut self, show: bool) -> Self {
+        self.show_segments = show;
+        self
+    }
+
+    /// Shows when the current duration and all durations end. `{segment}`
+    /// and `{all}` in `format` are replaced by the end times, formatted as
+    /// "%H:%M" unless given a format like `{all:%I:%M %p}`.
+    pub fn end_time_format(mut self, format: Option<String>) -> Self {
+        self.end_time_format = format;
+        self
+    }
+
+    pub fn build(self) -> TimerWidget {
+        let drawing = self.look.build();
+        let durations = if self.durations.is_empty() {
+            vec![Duration::minutes(5)]
+        } else {
+            self.durations
+        };
+        TimerWidget {
+            font: drawing.font,
+            style: drawing.style,
+            colors: drawing.colors,
+            theme: drawing.theme,
+            transition: drawing.transition,
+            repeat: self.repeat,
+            durations,
+            titles: self.titles,
+            execute: self.execute,
+            thresholds: self.thresholds,
+            show_progress: self.show_progress,
+            show_total_progress: self.show_total_progress,
+            show_segments: self.show_segments,
+            end_time_format: self.end_time_format,
+            auto_quit: self.auto_quit,
+            format: self.format,
+        }
+    }
+}
+
+/// The time a [`TimerWidget`] has been running, paused and resumed with the
+/// [`Pause`] trait.
+pub struct TimerState {
+    time_source: Rc<dyn TimeSource>,
     passed: Duration,
-    started_at: Option<DateTime<Local>>,
-    execute_result: RefCell<Option<String>>,
-    flash_state: RefCell<bool>, // Add this new field
+    started_at: Option<Instant>,
+    execute_result: Option<Result<String, String>>,
+}
+
+impl Default for TimerState {
+    fn default() -> Self {
+        Self::new(Rc::new(SystemClock::default()))
+    }
 }
 
-impl Timer {
-    #[allow(clippy::too_many_arguments)]
-    pub(crate) fn new(
-        size: u16,
-        style: Style,
-        durations: Vec<Duration>,
-        titles: Vec<String>,
-        repeat: bool,
-        format: DurationFormat,
-        paused: bool,
-        auto_quit: bool,
-        execute: Vec<String>,
-    ) -> Self {
+impl TimerState {
+    /// A timer running from now on, as told by `time_source`.
+    pub fn new(time_source: Rc<dyn TimeSource>) -> Self {
         Self {
-            size,
-            style,
-            durations,
-            titles,
-            repeat,
-            execute,
-            auto_quit,
-            format,
             passed: Duration::zero(),
-            started_at: (!paused).then(Local::now),
-            execute_result: RefCell::new(None),
-            flash_state: RefCell::new(false), // Initialize the new field
+            started_at: Some(time_source.instant()),
+            time_source,
+            execute_result: None,
         }
     }
 
-    pub(crate) fn remaining_time(&self) -> (Duration, usize) {
-        let total_passed = if let Some(started_at) = self.started_at {
-            self.passed + (Local::now() - started_at)
-        } else {
-            self.passed
-        };
+    /// Time the timer has been running, pauses excluded.
+    pub fn elapsed(&self) -> Duration {
+        match self.started_at {
+            Some(started_at) => self.passed + self.time_source.elapsed(started_at),
+            None => self.passed,
+        }
+    }
+
+    /// Starts over from the first duration, paused if it is paused.
+    pub fn reset(&mut self) {
+        self.passed = Duration::zero();
+        if self.started_at.is_some() {
+            self.started_at = Some(self.time_source.instant());
+        }
+        self.execute_result = None;
+    }
+
+    pub(crate) fn session_state(&self) -> SessionState {
+        SessionState::new(
+            SessionKind::Timer,
+            self.passed,
+            self.started_at,
+            &*self.time_source,
+        )
+    }
+
+    pub(crate) fn restore(&mut self, state: &SessionState) {
+        (self.passed, self.started_at) = state.restore(&*self.time_source);
+    }
+}
+
+impl TimerWidget {
+    pub fn builder() -> TimerWidgetBuilder {
+        TimerWidgetBuilder {
+            look: Look::default(),
+            repeat: false,
+            durations: Vec::new(),
+            titles: Vec::new(),
+            execute: Vec::new(),
+            thresholds: Vec::new(),
+            show_progress: false,
+            show_total_progress: false,
+            show_segments: false,
+            end_time_format: None,
+            auto_quit: false,
+            format: DurationFormat::HourMinSecDeci,
+        }
+    }
+
+    /// The remaining time of the current duration, zero or negative once the
+    /// last one is over, and the index of the current duration.
+    pub fn remaining_time(&self, state: &TimerState) -> (Duration, usize) {
+        let total_passed = state.elapsed();
 
         let mut idx = 0;
         let mut next_checkpoint = self.durations[idx];
-        while next_checkpoint < total_passed {
+        while next_checkpoint <= total_passed {
             if idx >= self.durations.len() - 1 && !self.repeat {
                 break;
             }
             idx = (idx + 1) % self.durations.len();
-            next_checkpoint = next_checkpoint + self.durations[idx];
+            next_checkpoint += self.durations[idx];
         }
 
         (next_checkpoint - total_passed, idx)
     }
 
-    pub(crate) fn is_finished(&self) -> bool {
-        return self.auto_quit && !self.execute_result.borrow().is_none();
+    /// Whether the last duration is over and the timer does not repeat.
+    pub fn is_over(&self, state: &TimerState) -> bool {
+        self.remaining_time(state).0 <= Duration::zero()
+    }
+
+    /// Ends the current duration now and goes on with the next one, or ends
+    /// the timer if it is the last one, paused or not.
+    pub fn skip(&self, state: &mut TimerState) {
+        let (remaining, _) = self.remaining_time(state);
+        if remaining > Duration::zero() {
+            state.passed += remaining;
+        }
+        self.tick(state);
+    }
+
+    /// Adds `duration` to the current duration. Once the timer is over, runs
+    /// the last duration again for that long.
+    /// Fails if the remaining time would be longer than [`MAX_DURATION`].
+    pub fn add(&self, state: &mut TimerState, duration: Duration) -> Result<(), String> {
+        let (remaining, _) = self.remaining_time(state);
+        match remaining.max(Duration::zero()).checked_add(&duration) {
+            Some(remaining) if remaining <= MAX_DURATION => {}
+            _ => return Err("The timer would run too long".to_string()),
+        }
+        if remaining <= Duration::zero() {
+            // back to the end of the last duration
+            state.passed += remaining;
+            state.execute_result = None;
+        }
+        state.passed -= duration;
+        Ok(())
+    }
+
+    /// Runs the command once the last duration is over, done on every render.
+    pub fn tick(&self, state: &mut TimerState) {
+        if self.is_over(state) && state.execute_result.is_none() {
+            if !self.execute.is_empty() {
+                state.execute_result = Some(execute(&self.execute));
+            } else {
+                state.execute_result = Some(Ok("".to_owned()))
+            }
+        }
+    }
+
+    /// The title, remaining time and state of the timer, with the list of
+    /// durations as tooltip.
+    pub fn status(&self, state: &TimerState) -> Status {
+        let (remaining, idx) = self.remaining_time(state);
+        let run_state = if remaining <= Duration::zero() {
+            RunState::Finished
+        } else if state.is_paused() {
+            RunState::Paused
+        } else if is_running_low(&self.thresholds, remaining) {
+            RunState::Warning
+        } else {
+            RunState::Running
+        };
+        let tooltip = self
+            .segment_lines(state, remaining, idx)
+            .iter()
+            .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect::<Vec<String>>()
+            .join("\n");
+        let duration = self.durations[idx];
+        Status {
+            title: self.title(idx),
+            remaining: Some(format_duration(remaining, self.format)),
+            elapsed: None,
+            time: None,
+            date: None,
+            tooltip: Some(tooltip),
+            segment: Some(idx),
+            percentage: Some(Progress::new("", duration - remaining, duration).percentage()),
+            state: run_state,
+        }
+    }
+
+    /// Whether the timer is over and was built with `auto_quit`, once it has
+    /// been rendered over and its command has run.
+    pub fn is_finished(&self, state: &TimerState) -> bool {
+        self.auto_quit && state.execute_result.is_some()
+    }
+
+    fn progress(&self, remaining: Duration, idx: usize) -> Vec<Progress> {
+        let sum = |durations: &[Duration]| durations.iter().fold(Duration::zero(), |a, d| a + *d);
+        let mut progress = Vec::new();
+        if self.show_progress {
+            let duration = self.durations[idx];
+            progress.push(Progress::new("", duration - remaining, duration));
+        }
+        if self.show_total_progress {
+            let done = sum(&self.durations[..=idx]) - remaining;
+            progress.push(Progress::new("Total ", done, sum(&self.durations)));
+        }
+        progress
+    }
+
+    fn title(&self, idx: usize) -> Option<String> {
+        if self.titles.is_empty() {
+            None
+        } else {
+            Some(self.titles[min(idx, self.titles.len() - 1)].clone())
+        }
+    }
+
+    fn segment_title(&self, idx: usize) -> String {
+        if self.titles.is_empty() {
+            format!("#{}", idx + 1)
+        } else {
+            self.titles[min(idx, self.titles.len() - 1)].clone()
+        }
+    }
+
+    /// Lines of the segment list, marking completed, current and upcoming
+    /// segments, with the wall-clock time the current and upcoming ones end.
+    fn segment_lines(&self, state: &TimerState, remaining: Duration, idx: usize) -> Vec<Line<'_>> {
+        let titles: Vec<String> = (0..self.durations.len())
+            .map(|i| self.segment_title(i))
+            .collect();
+        let title_width = titles.iter().map(|t| t.chars().count()).max().unwrap_or(0);
+        let finished = remaining <= Duration::zero();
+        let mut end = state.time_source.now() + remaining;
+        let mut lines = Vec::new();
+        for (i, (duration, title)) in self.durations.iter().zip(titles).enumerate() {
+            let (mark, end_time, style) = if i < idx || (i == idx && finished) {
+                (
+                    "✓",
+                    "done".to_string(),
+                    self.theme.footer.add_modifier(Modifier::DIM),
+                )
+            } else if i == idx {
+                ("▶", end.format("%H:%M").to_string(), self.theme.header)
+            } else {
+                end += *duration;
+                (" ", end.format("%H:%M").to_string(), self.theme.footer)
+            };
+            let duration = format_duration(*duration, DurationFormat::HourMinSec);
+            lines.push(Line::styled(
+                format!(
+                    "{} {:<title_width$}  {:>8}  {:>5}",
+                    mark, title, duration, end_time
+                ),
+                style,
+            ));
+        }
+        lines
+    }
+
+    /// Draws the segment list on the right of `area` if there is room, else at
+    /// its bottom, and returns the rest of `area`.
+    fn render_segments(
+        &self,
+        state: &TimerState,
+        area: Rect,
+        buf: &mut Buffer,
+        remaining: Duration,
+        idx: usize,
+    ) -> Rect {
+        let lines = self.segment_lines(state, remaining, idx);
+        let list_width = lines.iter().map(|l| l.width()).max().unwrap_or(0) as u16;
+        let list_height = lines.len() as u16;
+        let time_str = format_duration(remaining.abs(), self.format);
+        let text_width = ClockText::new(time_str, &*self.font, self.style).size().0;
+
+        self.theme.fill_background(area, buf);
+        let (rest, list_area) = if area.width >= text_width + list_width + 6 {
+            let rest = Rect {
+                width: area.width - list_width - 2,
+                ..area
+            };
+            let list_area = Rect {
+                x: rest.right(),
+                y: area.y + area.height.saturating_sub(list_height) / 2,
+                width: list_width,
+                height: min(list_height, area.height),
+            };
+            (rest, list_area)
+        } else {
+            let height = min(list_height, area.height / 2);
+            let rest = Rect {
+                height: area.height - height,
+                ..area
+            };
+            let list_area = Rect {
+                x: area.x + area.width.saturating_sub(list_width) / 2,
+                y: rest.bottom(),
+                width: min(list_width, area.width),
+                height,
+            };
+            (rest, list_area)
+        };
+        // Keep the current segment in view when the list is cut
+        let scroll = (idx as u16 + 1).saturating_sub(list_area.height);
+        Paragraph::new(lines)
+            .scroll((scroll, 0))
+            .render(list_area, buf);
+        rest
+    }
+
+    fn footer(&self, state: &TimerState, remaining: Duration, idx: usize) -> Vec<Span<'_>> {
+        let mut footer = Vec::new();
+        if let Some(format) = self.end_time_format.as_ref() {
+            if remaining > Duration::zero() {
+                // While paused, as if resumed now
+                let segment_end = state.time_source.now() + remaining;
+                let all_end = self.durations[idx + 1..]
+                    .iter()
+                    .fold(segment_end, |end, d| end + *d);
+                let line = format_end_time(format, segment_end, all_end);
+                footer.push(Span::styled(line, self.theme.footer));
+            }
+        }
+        if state.is_paused() {
+            footer.push(Span::styled(
+                "PAUSED (press <SPACE> to resume)",
+                self.theme.paused,
+            ));
+        } else {
+            match state.execute_result.clone() {
+                Some(Ok(output)) => footer.push(Span::styled(output, self.theme.footer)),
+                Some(Err(error)) => footer.push(Span::styled(error, self.theme.warning)),
+                None => {}
+            }
+        }
+        footer
+    }
+}
+
+/// Default format of the end time line of a timer.
+pub(crate) const DEFAULT_END_TIME_FORMAT: &str = "ends at {segment} (segment) / {all} (all)";
+
+fn end_time_regex() -> Regex {
+    Regex::new(r"\{(segment|all)(?::([^}]*))?\}").unwrap()
+}
+
+/// Fills `{segment}` and `{all}` in `format` with the end times of the current
+/// duration and of all durations. They are formatted as "%H:%M" unless they
+/// give their own strftime format, like `{all:%I:%M %p}`.
+fn format_end_time(format: &str, segment: DateTime<Local>, all: DateTime<Local>) -> String {
+    end_time_regex()
+        .replace_all(format, |cap: &Captures| {
+            let time = if &cap[1] == "segment" { segment } else { all };
+            let mut text = String::new();
+            // An invalid format is kept as is, it is checked for the CLI
+            if write!(
+                text,
+                "{}",
+                time.format(cap.get(2).map_or("%H:%M", |m| m.as_str()))
+            )
+            .is_err()
+            {
+                text = cap[0].to_string();
+            }
+            text
+        })
+        .into_owned()
+}
+
+/// Checks the strftime formats in an end time format, see `format_end_time`.
+pub(crate) fn parse_end_time_format(format: &str) -> Result<String, String> {
+    for cap in end_time_regex().captures_iter(format) {
+        if let Some(time_format) = cap.get(2) {
+            if StrftimeItems::new(time_format.as_str()).any(|item| matches!(item, Item::Error)) {
+                return Err(format!("Invalid end time format: {}", time_format.as_str()));
+            }
+        }
     }
+    Ok(format.to_string())
 }
 
-fn execute(execute: &[String]) -> String {
+fn execute(execute: &[String]) -> Result<String, String> {
     let mut cmd = Command::new("sh");
     cmd.arg("-c");
     let cmd_str = execute.join(" ");
@@ -91,112 +542,215 @@ fn execute(execute: &[String]) -> String {
     match output {
         Ok(output) => {
             if !output.status.success() {
-                format!("[ERROR] {}", String::from_utf8_lossy(&output.stderr))
+                Err(format!(
+                    "[ERROR] {}",
+                    String::from_utf8_lossy(&output.stderr)
+                ))
             } else {
-                format!("[SUCCEED] {}", String::from_utf8_lossy(&output.stdout))
+                Ok(format!(
+                    "[SUCCEED] {}",
+                    String::from_utf8_lossy(&output.stdout)
+                ))
             }
         }
-        Err(e) => {
-            format!("[FAILED] {}", e)
-        }
+        Err(e) => Err(format!("[FAILED] {}", e)),
     }
 }
 
-impl Widget for &Timer {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        let (remaining_time, idx) = self.remaining_time();
-
-        if remaining_time < Duration::zero() {
-            if self.execute_result.borrow().is_none() {
-                if !self.execute.is_empty() {
-                    let result = execute(&self.execute);
-                    *self.execute_result.borrow_mut() = Some(result);
-                } else {
-                    *self.execute_result.borrow_mut() = Some("".to_owned())
-                }
-            }
+impl StatefulWidget for &TimerWidget {
+    type State = TimerState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut TimerState) {
+        let (remaining_time, idx) = self.remaining_time(state);
+        let area = if self.show_segments {
+            self.render_segments(state, area, buf, remaining_time, idx)
+        } else {
+            area
+        };
 
+        self.tick(state);
+        if remaining_time <= Duration::zero() {
             // Flash the screen when timer is done
             let should_flash = remaining_time.num_milliseconds().abs() % 1000 < 500;
-            *self.flash_state.borrow_mut() = should_flash;
-
-            // Fill the entire area with the flash color
-            let flash_style = if *self.flash_state.borrow() {
-                Style::default().bg(Color::Green)
-            } else {
-                Style::default().bg(Color::Black)
-            };
-
-            // Fill the entire area with the flash color
-            for y in area.top()..area.bottom() {
-                for x in area.left()..area.right() {
-                    buf.get_mut(x, y).set_style(flash_style);
-                }
-            }
 
             // Only render the text during the visible phase
             if should_flash {
                 let elapsed_time = -remaining_time; // Make the time positive
                 let time_str = format_duration(elapsed_time, self.format);
-                let header = if self.titles.is_empty() {
-                    None
-                } else {
-                    Some(self.titles[min(idx, self.titles.len() - 1)].clone())
-                };
+                let header = self.title(idx);
 
-                let font = BricksFont::new(self.size);
                 let text = ClockText::new(
                     time_str.as_str().to_string(),
-                    &font,
-                    self.style.fg(Color::Black), // Make text visible on green background
+                    &*self.font,
+                    self.style.patch(self.theme.finished),
                 );
 
-                let footer = if self.is_paused() {
-                    Some("PAUSED (press <SPACE> to resume)".to_string())
-                } else {
-                    self.execute_result.borrow().clone()
+                // Fill the entire area with the flash color
+                let flash_theme = Theme {
+                    background: self.theme.finished.bg,
+                    ..self.theme.clone()
                 };
-
-                render_centered(area, buf, &text, header, footer);
+                render_centered(
+                    area,
+                    buf,
+                    &text,
+                    &flash_theme,
+                    header,
+                    self.footer(state, remaining_time, idx),
+                    &self.progress(remaining_time, idx),
+                );
+            } else {
+                self.theme.fill_background(area, buf);
             }
         } else {
             // Normal rendering logic when timer has not reached zero
             let time_str = format_duration(remaining_time, self.format);
-            let header = if self.titles.is_empty() {
-                None
-            } else {
-                Some(self.titles[min(idx, self.titles.len() - 1)].clone())
-            };
+            let header = self.title(idx);
 
-            let font = BricksFont::new(self.size);
-            let text = ClockText::new(time_str.as_str().to_string(), &font, self.style);
+            let colors = threshold_colors(&self.thresholds, remaining_time);
+            let text = ClockText::new(time_str.as_str().to_string(), &*self.font, self.style)
+                .with_colors(colors.as_ref().unwrap_or(&self.colors), true)
+                .with_transition(self.transition.as_ref())
+                .with_time_source(&*state.time_source);
 
-            let footer = if self.is_paused() {
-                Some("PAUSED (press <SPACE> to resume)".to_string())
-            } else {
-                self.execute_result.borrow().clone()
-            };
-
-            render_centered(area, buf, &text, header, footer);
+            render_centered(
+                area,
+                buf,
+                &text,
+                &self.theme,
+                header,
+                self.footer(state, remaining_time, idx),
+                &self.progress(remaining_time, idx),
+            );
         }
     }
 }
 
-impl Pause for Timer {
+impl Pause for TimerState {
     fn is_paused(&self) -> bool {
         self.started_at.is_none()
     }
 
     fn pause(&mut self) {
         if let Some(started_at) = self.started_at {
-            self.passed = self.passed + (Local::now() - started_at);
+            self.passed += self.time_source.elapsed(started_at);
             self.started_at = None;
         }
     }
 
     fn resume(&mut self) {
         if self.started_at.is_none() {
-            self.started_at = Some(Local::now());
+            self.started_at = Some(self.time_source.instant());
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::time_source::ManualClock;
+
+    fn at(h: u32, m: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 1, 1, h, m, 0).unwrap()
+    }
+
+    /// The end time line of a timer of 25m and 5m in `state`.
+    fn end_time_line(state: &TimerState, format: &str) -> String {
+        let timer = TimerWidget::builder()
+            .durations(vec![Duration::minutes(25), Duration::minutes(5)])
+            .end_time_format(Some(format.to_string()))
+            .build();
+        let (remaining, idx) = timer.remaining_time(state);
+        timer.footer(state, remaining, idx)[0].content.to_string()
+    }
+
+    #[test]
+    fn format_end_times() {
+        let (segment, all) = (at(14, 35), at(15, 10));
+        assert_eq!(
+            format_end_time(DEFAULT_END_TIME_FORMAT, segment, all),
+            "ends at 14:35 (segment) / 15:10 (all)"
+        );
+        assert_eq!(
+            format_end_time("done by {all:%I:%M %p}", segment, all),
+            "done by 03:10 PM"
+        );
+        assert_eq!(
+            format_end_time("{segment:%H:%M:%S} {nosuch}", segment, all),
+            "14:35:00 {nosuch}"
+        );
+        // an invalid format is kept as is
+        assert_eq!(format_end_time("{all:%Q}", segment, all), "{all:%Q}");
+    }
+
+    #[test]
+    fn parse_end_time_formats() {
+        assert!(parse_end_time_format(DEFAULT_END_TIME_FORMAT).is_ok());
+        assert!(parse_end_time_format("{all:%I:%M %p}").is_ok());
+        assert!(parse_end_time_format("no times at all").is_ok());
+        assert!(parse_end_time_format("{all:%Q}").is_err());
+        assert!(parse_end_time_format("{segment:%H} {all:%}").is_err());
+    }
+
+    #[test]
+    fn end_times_while_paused() {
+        let clock = Rc::new(ManualClock::new(at(12, 0)));
+        let mut state = TimerState::new(clock.clone());
+        clock.advance(std::time::Duration::from_secs(5 * 60));
+        assert_eq!(
+            end_time_line(&state, DEFAULT_END_TIME_FORMAT),
+            "ends at 12:25 (segment) / 12:30 (all)"
+        );
+
+        // as if resumed now, so they move on while paused
+        state.pause();
+        clock.advance(std::time::Duration::from_secs(10 * 60));
+        assert_eq!(
+            end_time_line(&state, DEFAULT_END_TIME_FORMAT),
+            "ends at 12:35 (segment) / 12:40 (all)"
+        );
+    }
+
+    #[test]
+    fn end_times_across_midnight() {
+        let clock = Rc::new(ManualClock::new(at(23, 50)));
+        let state = TimerState::new(clock.clone());
+        assert_eq!(
+            end_time_line(&state, DEFAULT_END_TIME_FORMAT),
+            "ends at 00:15 (segment) / 00:20 (all)"
+        );
+        assert_eq!(
+            end_time_line(&state, "{segment:%a %H:%M} / {all:%F %H:%M}"),
+            "Thu 00:15 / 2025-01-02 00:20"
+        );
+    }
+
+    #[test]
+    fn skip_to_the_end_while_paused() {
+        let clock = Rc::new(ManualClock::new(at(12, 0)));
+        let mut state = TimerState::new(clock.clone());
+        state.pause();
+        let timer = TimerWidget::builder()
+            .durations(vec![Duration::minutes(25), Duration::minutes(5)])
+            .auto_quit(true)
+            .build();
+
+        timer.skip(&mut state);
+        assert_eq!(timer.remaining_time(&state), (Duration::minutes(5), 1));
+        assert!(!timer.is_over(&state));
+
+        timer.skip(&mut state);
+        assert_eq!(timer.remaining_time(&state), (Duration::zero(), 1));
+        assert!(timer.is_over(&state));
+        assert_eq!(timer.status(&state).state, RunState::Finished);
+        // the command has run, so it quits
+        assert!(timer.is_finished(&state));
+
+        // and goes on from the end of the last duration when time is added
+        timer.add(&mut state, Duration::minutes(1)).unwrap();
+        assert_eq!(timer.remaining_time(&state), (Duration::minutes(1), 1));
+        assert!(!timer.is_finished(&state));
+    }
+}
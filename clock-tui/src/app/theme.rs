@@ -0,0 +1,173 @@
+use std::path::Path;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+};
+
+use crate::clock_text::color::ColorSpec;
+use crate::clock_text::font::effect::Effect;
+use crate::config::{self, ThemeConfig};
+
+use super::parse_effect;
+
+/// Colors of everything drawn by the modes, picked with `--theme` or the
+/// `[theme]` section of the config file.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    /// Color of the digits, unless set with `--color`
+    pub foreground: ColorSpec,
+    /// Fill of the whole area, `None` keeps the terminal background
+    pub background: Option<Color>,
+    pub header: Style,
+    pub footer: Style,
+    /// Footer while paused
+    pub paused: Style,
+    /// Messages about something going wrong, like a failed timer command
+    pub warning: Style,
+    /// Digits and fill of the flash when a timer is finished
+    pub finished: Style,
+    /// Effect on the digits, unless set with `--effect`
+    pub effect: Option<Effect>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            foreground: ColorSpec::Solid(Color::Green),
+            background: None,
+            header: Style::default(),
+            footer: Style::default(),
+            paused: Style::default(),
+            warning: Style::default().fg(Color::Yellow),
+            finished: Style::default().fg(Color::Black).bg(Color::Green),
+            effect: None,
+        }
+    }
+}
+
+impl Theme {
+    pub fn builtin(name: &str) -> Option<Self> {
+        let rgb = |hex: u32| {
+            let [_, r, g, b] = hex.to_be_bytes();
+            Color::Rgb(r, g, b)
+        };
+        let fg = |hex: u32| Style::default().fg(rgb(hex));
+        let theme = match name.to_lowercase().as_str() {
+            "default" => Self::default(),
+            "dark" => Self {
+                foreground: ColorSpec::Solid(rgb(0x5fd7ff)),
+                background: Some(rgb(0x121212)),
+                header: fg(0xbcbcbc).add_modifier(Modifier::BOLD),
+                footer: fg(0x808080),
+                paused: fg(0xffd75f),
+                warning: fg(0xff875f),
+                finished: fg(0x121212).bg(rgb(0x5fd7ff)),
+                effect: None,
+            },
+            "light" => Self {
+                foreground: ColorSpec::Solid(rgb(0x005f87)),
+                background: Some(rgb(0xfafafa)),
+                header: fg(0x3a3a3a).add_modifier(Modifier::BOLD),
+                footer: fg(0x767676),
+                paused: fg(0xaf8700),
+                warning: fg(0xd75f00),
+                finished: fg(0xfafafa).bg(rgb(0x005f87)),
+                effect: None,
+            },
+            "solarized" => Self {
+                foreground: ColorSpec::Solid(rgb(0x268bd2)),
+                background: Some(rgb(0x002b36)),
+                header: fg(0x93a1a1).add_modifier(Modifier::BOLD),
+                footer: fg(0x586e75),
+                paused: fg(0xb58900),
+                warning: fg(0xcb4b16),
+                finished: fg(0x002b36).bg(rgb(0x859900)),
+                effect: None,
+            },
+            "high-contrast" | "highcontrast" => Self {
+                foreground: ColorSpec::Solid(Color::White),
+                background: Some(Color::Black),
+                header: Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+                footer: Style::default().fg(Color::White),
+                paused: Style::default()
+                    .fg(Color::LightYellow)
+                    .add_modifier(Modifier::BOLD),
+                warning: Style::default()
+                    .fg(Color::LightRed)
+                    .add_modifier(Modifier::BOLD),
+                finished: Style::default().fg(Color::Black).bg(Color::White),
+                effect: None,
+            },
+            _ => return None,
+        };
+        Some(theme)
+    }
+
+    /// Loads a built-in theme, a theme file at `name` if it looks like a path,
+    /// or `~/.config/tclock/themes/<name>.toml`. A theme file overrides the
+    /// built-in theme it names, or the default one.
+    pub fn load(name: &str) -> Result<Self, String> {
+        if let Some(theme) = Self::builtin(name) {
+            return Ok(theme);
+        }
+        let path = Path::new(name);
+        let path = if path.extension().is_some() || path.components().count() > 1 {
+            path.to_path_buf()
+        } else {
+            config::config_dir()
+                .map(|dir| dir.join("themes").join(format!("{}.toml", name)))
+                .ok_or_else(|| format!("Invalid theme: {}", name))?
+        };
+        if !path.exists() {
+            return Err(format!(
+                "Invalid theme: {}, {} not found",
+                name,
+                path.display()
+            ));
+        }
+        let config = ThemeConfig::load(&path)?;
+        let base = match config.name.as_deref() {
+            Some(base) => Self::builtin(base).ok_or_else(|| {
+                format!("Invalid theme {}: {} is not a built-in theme", name, base)
+            })?,
+            None => Self::default(),
+        };
+        base.with_config(&config)
+    }
+
+    /// Returns this theme with the fields set in `config` replaced.
+    pub fn with_config(mut self, config: &ThemeConfig) -> Result<Self, String> {
+        if let Some(foreground) = &config.foreground {
+            self.foreground = foreground.clone();
+        }
+        if let Some(background) = config.background {
+            self.background = Some(background);
+        }
+        for (style, style_config) in [
+            (&mut self.header, &config.header),
+            (&mut self.footer, &config.footer),
+            (&mut self.paused, &config.paused),
+            (&mut self.warning, &config.warning),
+            (&mut self.finished, &config.finished),
+        ] {
+            if let Some(style_config) = style_config {
+                *style = style_config.to_style();
+            }
+        }
+        if let Some(effect) = &config.effect {
+            self.effect = Some(parse_effect(effect)?);
+        }
+        Ok(self)
+    }
+
+    /// Fills `area` with the background color, if any.
+    pub fn fill_background(&self, area: Rect, buf: &mut Buffer) {
+        if let Some(background) = self.background {
+            buf.set_style(area, Style::default().bg(background));
+        }
+    }
+}
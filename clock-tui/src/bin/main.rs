@@ -1,40 +1,350 @@
 use std::error::Error;
 use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
 use std::time::Duration;
 
 use clap::Parser;
+use clock_tui::app::action::Action;
+use clock_tui::app::bar::{parse_click, BarFormat};
 use clock_tui::app::App;
-use clock_tui::app::Mode;
-use crossterm::event::{self, Event, KeyCode};
+use clock_tui::app::{Command, Mode};
+use crossterm::event::{self, Event, KeyCode, KeyModifiers};
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
 use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen};
 use crossterm::ExecutableCommand;
 use ratatui::backend::CrosstermBackend;
-use ratatui::Terminal;
+use ratatui::{Terminal, TerminalOptions, Viewport};
+
+/// How often the status line is checked for changes with `--plain`, `--i3bar` or `--waybar`.
+const BAR_TICK_RATE: Duration = Duration::from_millis(50);
+
+/// How long the main loops get to exit on SIGTERM, SIGINT or SIGHUP before
+/// the process exits anyway.
+const QUIT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Set once the app has exited after the main loop, so a quit signal does
+/// not need to force the exit.
+static EXITED: AtomicBool = AtomicBool::new(false);
+
+/// What the main loops do on a Unix signal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Signal {
+    /// SIGTERM, SIGINT or SIGHUP: exit like on `q`, then like killed by the signal
+    Quit(i32),
+    /// SIGTSTP: restore the terminal and stop
+    Suspend,
+    /// SIGCONT: redraw, the screen may have changed while stopped
+    Continue,
+    /// SIGUSR1: toggle pause
+    TogglePause,
+    /// SIGUSR2: skip to the next duration of a timer, or lap a stopwatch
+    Next,
+}
 
 fn main() -> Result<(), Box<dyn Error>> {
     // Parse command line arguments
     // Must be done first so `--help` isn't printed to the alternate screen.
     let mut app = App::parse();
 
+    match app.command.take() {
+        Some(Command::Ctl { name, command }) => return ctl(&name, &command.join(" ")),
+        Some(Command::Mode(mode)) => app.mode = Some(mode),
+        None => {}
+    }
+
+    // Load config and initialize app
+    // Done before setting up the terminal so config errors are printed to the normal screen.
+    if let Err(e) = app.init_app() {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+
+    let signals = watch_signals()?;
+
+    if let Some(format) = app.bar_format() {
+        let quit = run_bar(&mut app, format, &signals)?;
+        app.on_exit();
+        exit(quit);
+        return Ok(());
+    }
+
     // Setup terminal
+    // An inline viewport is drawn under the prompt, without the alternate screen.
+    let viewport = match app.inline {
+        Some(rows) => Viewport::Inline(rows),
+        None => Viewport::Fullscreen,
+    };
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    stdout.execute(EnterAlternateScreen)?;
+    if viewport == Viewport::Fullscreen {
+        stdout.execute(EnterAlternateScreen)?;
+    }
     let backend = CrosstermBackend::new(&mut stdout);
-    let mut terminal = Terminal::new(backend)?;
+    let mut terminal = Terminal::with_options(backend, TerminalOptions { viewport })?;
 
-    // Load config and initialize app
-    app.init_app();
+    let result = run(&mut terminal, &mut app, &signals);
+
+    // restore terminal
+    // Errors do not stop here, eg. the terminal is gone on SIGHUP but the
+    // session must still be saved.
+    let restored = restore_terminal(&mut terminal, app.inline.is_some());
+
+    // Perform logic such as printing the stopwatch time.
+    // Must be done after leaving alternate screen.
+    app.on_exit();
+    let _ = io::stdout().flush();
+
+    match result.and_then(|quit| restored.map(|_| quit)) {
+        Ok(quit) => exit(quit),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+
+    Ok(())
+}
+
+/// Tells the signal thread the app has exited, and exits with 128 plus the
+/// number of the signal that quit it, if any, like the shells expect.
+fn exit(quit: Option<i32>) {
+    EXITED.store(true, Ordering::SeqCst);
+    if let Some(signo) = quit {
+        let _ = io::stdout().flush();
+        std::process::exit(128 + signo);
+    }
+}
+
+/// Leaves raw mode and the alternate screen, or with `inline` keeps the last
+/// frame and goes on below it.
+fn restore_terminal<W: Write>(
+    terminal: &mut Terminal<CrosstermBackend<W>>,
+    inline: bool,
+) -> Result<(), Box<dyn Error>> {
+    if inline {
+        let area = terminal.get_frame().size();
+        terminal.set_cursor(0, area.bottom().saturating_sub(1))?;
+    }
+    terminal.show_cursor()?;
+    disable_raw_mode()?;
+    if inline {
+        writeln!(terminal.backend_mut())?;
+    } else {
+        terminal.backend_mut().execute(LeaveAlternateScreen)?;
+    }
+    terminal.backend_mut().flush()?;
+    Ok(())
+}
+
+/// Sets up the terminal again after `restore_terminal`, and draws the whole
+/// frame on the next draw.
+fn setup_terminal<W: Write>(
+    terminal: &mut Terminal<CrosstermBackend<W>>,
+    inline: bool,
+) -> Result<(), Box<dyn Error>> {
+    enable_raw_mode()?;
+    if !inline {
+        terminal.backend_mut().execute(EnterAlternateScreen)?;
+    }
+    terminal.clear()?;
+    Ok(())
+}
+
+/// Receives the signals handled by the main loops, from another thread.
+#[cfg(unix)]
+fn watch_signals() -> io::Result<Receiver<Signal>> {
+    use signal_hook::consts::signal::*;
+    use signal_hook::iterator::Signals;
 
+    let mut signals = Signals::new([SIGTERM, SIGINT, SIGHUP, SIGTSTP, SIGCONT, SIGUSR1, SIGUSR2])?;
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for signal in signals.forever() {
+            let signal = match signal {
+                SIGTSTP => Signal::Suspend,
+                SIGCONT => Signal::Continue,
+                SIGUSR1 => Signal::TogglePause,
+                SIGUSR2 => Signal::Next,
+                signo => Signal::Quit(signo),
+            };
+            if tx.send(signal).is_err() {
+                break;
+            }
+            if let Signal::Quit(signo) = signal {
+                // Once the terminal hangs up, crossterm keeps reading it in
+                // `event::poll` and the main loop never gets the signal. The
+                // session is saved on every change, so exit anyway.
+                std::thread::sleep(QUIT_TIMEOUT);
+                if !EXITED.load(Ordering::SeqCst) {
+                    std::process::exit(128 + signo);
+                }
+            }
+        }
+    });
+    Ok(rx)
+}
+
+#[cfg(not(unix))]
+fn watch_signals() -> io::Result<Receiver<Signal>> {
+    Ok(mpsc::channel().1)
+}
+
+/// Stops the process like SIGTSTP does by default, returns once continued.
+#[cfg(unix)]
+fn stop() -> io::Result<()> {
+    signal_hook::low_level::emulate_default_handler(signal_hook::consts::SIGTSTP)
+}
+
+#[cfg(not(unix))]
+fn stop() -> io::Result<()> {
+    Ok(())
+}
+
+/// Pauses or goes on as told by SIGUSR1 and SIGUSR2.
+fn on_signal_action(app: &mut App, signal: Signal) {
+    match signal {
+        Signal::TogglePause => {
+            let _ = app.on_action(Action::TogglePause);
+        }
+        Signal::Next => {
+            // only a timer skips and only a stopwatch laps
+            let _ = app
+                .on_action(Action::Skip)
+                .or_else(|_| app.on_action(Action::Lap));
+        }
+        _ => {}
+    }
+}
+
+/// Sends `command` to the tclock listening with `--control name` and prints
+/// its reply, exits with 1 if it is an error.
+#[cfg(unix)]
+fn ctl(name: &str, command: &str) -> Result<(), Box<dyn Error>> {
+    match clock_tui::app::control::send(name, command) {
+        Ok(reply) if !reply.starts_with("error: ") => {
+            println!("{}", reply);
+            Ok(())
+        }
+        Ok(reply) => {
+            eprintln!("{}", reply);
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn ctl(_name: &str, _command: &str) -> Result<(), Box<dyn Error>> {
+    eprintln!("ctl is only supported on Unix");
+    std::process::exit(1);
+}
+
+/// Prints the status line of the app whenever it changes, without a TUI.
+fn run_bar(
+    app: &mut App,
+    format: BarFormat,
+    signals: &Receiver<Signal>,
+) -> Result<Option<i32>, Box<dyn Error>> {
+    let mut stdout = io::stdout();
+    if let Some(header) = format.header() {
+        writeln!(stdout, "{}", header)?;
+    }
+    let actions = (format == BarFormat::I3bar).then(read_clicks);
+    let mut last_line = None;
+    loop {
+        app.tick();
+        let line = app.status_line(format);
+        if line != last_line {
+            if let Some(ref line) = line {
+                writeln!(stdout, "{}", line)?;
+                stdout.flush()?;
+            }
+            last_line = line;
+        }
+        if app.is_ended() {
+            break;
+        }
+        std::thread::sleep(BAR_TICK_RATE);
+        for action in actions.iter().flat_map(|rx| rx.try_iter()) {
+            let _ = app.on_action(action);
+        }
+        app.poll_control();
+        if let Some(e) = app.take_error() {
+            eprintln!("{}", e);
+        }
+        for signal in signals.try_iter() {
+            match signal {
+                Signal::Quit(signo) => return Ok(Some(signo)),
+                Signal::Suspend => stop()?,
+                _ => on_signal_action(app, signal),
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Reads i3bar click events from stdin on another thread.
+fn read_clicks() -> Receiver<Action> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in io::stdin().lines() {
+            let Ok(line) = line else { break };
+            if let Some(action) = parse_click(&line) {
+                if tx.send(action).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    rx
+}
+
+/// Gives the terminal back and stops, until continued.
+fn suspend<W: Write>(
+    terminal: &mut Terminal<CrosstermBackend<W>>,
+    app: &App,
+) -> Result<(), Box<dyn Error>> {
+    restore_terminal(terminal, app.inline.is_some())?;
+    stop()?;
+    setup_terminal(terminal, app.inline.is_some())
+}
+
+/// Draws the app until it ends or is quit, returns the signal that quit it, if any.
+fn run<W: Write>(
+    terminal: &mut Terminal<CrosstermBackend<W>>,
+    app: &mut App,
+    signals: &Receiver<Signal>,
+) -> Result<Option<i32>, Box<dyn Error>> {
     loop {
         if app.is_ended() {
             break;
         }
         terminal.draw(|f| app.ui(f))?;
+        app.poll_control();
+        for signal in signals.try_iter() {
+            match signal {
+                Signal::Quit(signo) => return Ok(Some(signo)),
+                Signal::Suspend => suspend(terminal, app)?,
+                Signal::Continue => terminal.clear()?,
+                _ => on_signal_action(app, signal),
+            }
+        }
 
-        if event::poll(Duration::from_millis(250))? {
+        if event::poll(app.tick_rate())? {
             if let Event::Key(key) = event::read()? {
+                // Raw mode turns Ctrl-C and Ctrl-Z into keys instead of signals
+                if key.modifiers.contains(KeyModifiers::CONTROL) {
+                    match key.code {
+                        KeyCode::Char('c') => break,
+                        KeyCode::Char('z') => suspend(terminal, app)?,
+                        _ => {}
+                    }
+                    continue;
+                }
                 match key.code {
                     KeyCode::Char('q') => break,
                     KeyCode::Char(' ') => app.on_key(KeyCode::Char(' ')),
@@ -43,8 +353,8 @@ fn main() -> Result<(), Box<dyn Error>> {
                         no_date: false,
                         no_seconds: false,
                         millis: false,
-                    }),
-                    KeyCode::Char('w') => app.set_mode(Mode::Stopwatch),
+                    })?,
+                    KeyCode::Char('w') => app.set_mode(Mode::Stopwatch)?,
                     KeyCode::Char('t') => app.set_mode(Mode::Timer {
                         durations: vec![],
                         titles: vec![],
@@ -53,23 +363,17 @@ fn main() -> Result<(), Box<dyn Error>> {
                         paused: false,
                         auto_quit: false,
                         execute: vec![],
-                    }),
+                        progress: false,
+                        total_progress: false,
+                        segments: false,
+                        end_time: false,
+                        end_time_format: None,
+                    })?,
                     _ => {}
                 }
             }
         }
     }
 
-    // restore terminal
-    terminal.show_cursor()?;
-    drop(terminal);
-    disable_raw_mode()?;
-    stdout.execute(LeaveAlternateScreen)?;
-
-    // Perform logic such as printing the stopwatch time.
-    // Must be done after leaving alternate screen.
-    app.on_exit();
-    io::stdout().flush()?;
-
-    Ok(())
+    Ok(None)
 }
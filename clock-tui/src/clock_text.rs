@@ -1,30 +1,137 @@
-use ratatui::{style::Style, widgets::Widget};
+use chrono::{DateTime, Local};
+use ratatui::{buffer::Buffer, layout::Rect, style::Style, widgets::Widget};
 
+use crate::clock_text::color::{ColorSpec, TextColors, TimePart};
 use crate::clock_text::font::Font;
+use crate::clock_text::transition::TransitionState;
+use crate::time_source::{SystemClock, TimeSource};
 
+pub mod color;
 pub mod font;
 pub mod point;
+pub mod transition;
 
 #[derive(Clone)]
 pub struct ClockText<'a> {
     pub text: String,
     pub font: &'a dyn Font,
     pub style: Style,
+    pub transition: Option<&'a TransitionState>,
+    pub colors: Option<&'a TextColors>,
+    /// Whether the last group of digits is seconds rather than minutes, see `TimePart::of_text`.
+    pub ends_with_seconds: bool,
+    /// Where transitions and the rainbow get the time from, the system time if `None`.
+    pub time_source: Option<&'a dyn TimeSource>,
 }
 
 impl<'a> ClockText<'a> {
     pub fn new(text: String, font: &'a dyn Font, style: Style) -> ClockText<'a> {
-        ClockText { text, font, style }
+        ClockText {
+            text,
+            font,
+            style,
+            transition: None,
+            colors: None,
+            ends_with_seconds: true,
+            time_source: None,
+        }
     }
+
+    /// Animates the glyphs that changed since the text last rendered with `transition`.
+    pub fn with_transition(mut self, transition: Option<&'a TransitionState>) -> ClockText<'a> {
+        self.transition = transition;
+        self
+    }
+
+    /// Colors each part of the time with `colors`, overriding the foreground of `style`.
+    pub fn with_colors(mut self, colors: &'a TextColors, ends_with_seconds: bool) -> ClockText<'a> {
+        self.colors = Some(colors);
+        self.ends_with_seconds = ends_with_seconds;
+        self
+    }
+
+    /// Animates with the time of `time_source` instead of the system time.
+    pub fn with_time_source(mut self, time_source: &'a dyn TimeSource) -> ClockText<'a> {
+        self.time_source = Some(time_source);
+        self
+    }
+
     pub fn size(&self) -> (u16, u16) {
-        let width = self.text.chars().count() as u16 * (self.font.get_char_width() + 2) - 2;
+        let width = self
+            .text
+            .chars()
+            .map(|c| self.font.get_char_advance(c) + self.font.get_spacing())
+            .sum::<u16>()
+            .saturating_sub(self.font.get_spacing());
         let height = self.font.get_char_height();
         (width, height)
     }
 }
 
 impl<'a> Widget for ClockText<'a> {
-    fn render(self, area: ratatui::layout::Rect, buf: &mut ratatui::buffer::Buffer) {
-        self.font.draw_str(&self.text, area, self.style, buf);
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if self.transition.is_none() && self.colors.is_none() {
+            self.font.draw_str(&self.text, area, self.style, buf);
+            return;
+        }
+
+        let system = SystemClock::default();
+        let time = self.time_source.unwrap_or(&system);
+        if let Some(transition) = self.transition {
+            transition.update(&self.text, time.instant());
+        }
+        let now = time.now();
+        let parts = TimePart::of_text(&self.text, self.ends_with_seconds);
+        let width = self.size().0.saturating_sub(1).max(1) as f32;
+        let offset = |x: u16| (x - area.x) as f32 / width;
+        let mut x = area.x;
+        for (idx, c) in self.text.chars().enumerate() {
+            let advance = self.font.get_char_advance(c);
+            if x + advance > area.right() {
+                break;
+            }
+            let spec = self.colors.map(|colors| colors.get(parts[idx]));
+            let style = match spec {
+                Some(spec) => self.style.fg(spec.color_at(offset(x), now)),
+                None => self.style,
+            };
+            let animating = self
+                .transition
+                .and_then(|t| t.get_progress(idx).map(|progress| (t, progress)));
+            match animating {
+                Some((transition, (from, progress))) => {
+                    transition.draw_char(self.font, from, c, progress, x, area.y, style, buf)
+                }
+                None => self.font.draw_char(c, x, area.y, style, buf),
+            }
+            if let Some(spec) = spec.filter(|spec| !spec.is_solid()) {
+                let right = (x + advance).min(buf.area.right());
+                let bottom = (area.y + self.font.get_char_height()).min(buf.area.bottom());
+                recolor(spec, style, x..right, area.y..bottom, offset, now, buf);
+            }
+            x += advance + self.font.get_spacing();
+        }
+    }
+}
+
+/// Gives the cells drawn in `style` their own color along `spec`. Cells drawn
+/// in another color, like the shadow of an effect, keep it.
+fn recolor(
+    spec: &ColorSpec,
+    style: Style,
+    cols: std::ops::Range<u16>,
+    rows: std::ops::Range<u16>,
+    offset: impl Fn(u16) -> f32,
+    now: DateTime<Local>,
+    buf: &mut Buffer,
+) {
+    for col in cols {
+        let color = spec.color_at(offset(col), now);
+        for row in rows.clone() {
+            let cell = buf.get_mut(col, row);
+            if cell.symbol() != " " && Some(cell.fg) == style.fg {
+                cell.fg = color;
+            }
+        }
     }
 }
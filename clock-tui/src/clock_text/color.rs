@@ -0,0 +1,580 @@
+use chrono::{DateTime, Local};
+use ratatui::style::Color;
+use regex::Regex;
+
+/// Degrees per second the hue of `ColorSpec::Rainbow` moves.
+const RAINBOW_SPEED: f32 = 20.0;
+
+/// How a part of a `ClockText` is colored.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColorSpec {
+    Solid(Color),
+    /// Linear gradient across the whole text, through evenly spaced stops.
+    Gradient(Vec<Color>),
+    /// A full hue spectrum across the text, slowly cycling.
+    Rainbow,
+}
+
+impl ColorSpec {
+    pub fn is_solid(&self) -> bool {
+        matches!(self, ColorSpec::Solid(_))
+    }
+
+    /// Returns the color at `t`, from 0.0 on the left of the text to 1.0 on the
+    /// right, when it is `now`.
+    pub fn color_at(&self, t: f32, now: DateTime<Local>) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            ColorSpec::Solid(color) => *color,
+            ColorSpec::Gradient(stops) if stops.len() < 2 => {
+                stops.first().copied().unwrap_or(Color::Reset)
+            }
+            ColorSpec::Gradient(stops) => {
+                let pos = t * (stops.len() - 1) as f32;
+                let idx = (pos.floor() as usize).min(stops.len() - 2);
+                let (from, to) = (to_rgb(stops[idx]), to_rgb(stops[idx + 1]));
+                let frac = pos - idx as f32;
+                let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * frac).round() as u8;
+                Color::Rgb(mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+            }
+            ColorSpec::Rainbow => {
+                hsl_to_rgb(t * 360.0 + rainbow_phase(now.timestamp_millis()), 1.0, 0.6)
+            }
+        }
+    }
+}
+
+/// The part of a time a char of a `ClockText` belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimePart {
+    Hours,
+    Minutes,
+    Seconds,
+    Separator,
+}
+
+impl TimePart {
+    /// Splits a time like "1:02:03.4" into its parts. Groups are counted from
+    /// the right, `ends_with_seconds` tells whether the last one is seconds or
+    /// minutes. Days are counted as hours and fractions as seconds.
+    pub fn of_text(text: &str, ends_with_seconds: bool) -> Vec<TimePart> {
+        let groups = text.split(':').count();
+        let mut group = 0;
+        let mut in_fraction = false;
+        text.chars()
+            .map(|c| {
+                if c.is_ascii_digit() {
+                    let from_right = groups - 1 - group + usize::from(!ends_with_seconds);
+                    match from_right {
+                        _ if in_fraction => TimePart::Seconds,
+                        0 => TimePart::Seconds,
+                        1 => TimePart::Minutes,
+                        _ => TimePart::Hours,
+                    }
+                } else {
+                    if c == ':' {
+                        group += 1;
+                    }
+                    in_fraction = c == '.';
+                    TimePart::Separator
+                }
+            })
+            .collect()
+    }
+}
+
+/// Colors of each part of a `ClockText`, parts without a color use `base`.
+#[derive(Debug, Clone)]
+pub struct TextColors {
+    pub base: ColorSpec,
+    pub hours: Option<ColorSpec>,
+    pub minutes: Option<ColorSpec>,
+    pub seconds: Option<ColorSpec>,
+    pub separators: Option<ColorSpec>,
+}
+
+impl TextColors {
+    pub fn new(base: ColorSpec) -> Self {
+        Self {
+            base,
+            hours: None,
+            minutes: None,
+            seconds: None,
+            separators: None,
+        }
+    }
+
+    pub fn get(&self, part: TimePart) -> &ColorSpec {
+        let spec = match part {
+            TimePart::Hours => &self.hours,
+            TimePart::Minutes => &self.minutes,
+            TimePart::Seconds => &self.seconds,
+            TimePart::Separator => &self.separators,
+        };
+        spec.as_ref().unwrap_or(&self.base)
+    }
+}
+
+/// Approximates `color` in RGB, named colors use the xterm palette.
+pub fn to_rgb(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Black => (0, 0, 0),
+        Color::Red => (205, 0, 0),
+        Color::Green => (0, 205, 0),
+        Color::Yellow => (205, 205, 0),
+        Color::Blue => (0, 0, 238),
+        Color::Magenta => (205, 0, 205),
+        Color::Cyan => (0, 205, 205),
+        Color::Gray => (229, 229, 229),
+        Color::DarkGray => (127, 127, 127),
+        Color::LightRed => (255, 0, 0),
+        Color::LightGreen => (0, 255, 0),
+        Color::LightYellow => (255, 255, 0),
+        Color::LightBlue => (92, 92, 255),
+        Color::LightMagenta => (255, 0, 255),
+        Color::LightCyan => (0, 255, 255),
+        Color::White => (255, 255, 255),
+        Color::Indexed(i) if i < 16 => to_rgb(
+            [
+                Color::Black,
+                Color::Red,
+                Color::Green,
+                Color::Yellow,
+                Color::Blue,
+                Color::Magenta,
+                Color::Cyan,
+                Color::Gray,
+                Color::DarkGray,
+                Color::LightRed,
+                Color::LightGreen,
+                Color::LightYellow,
+                Color::LightBlue,
+                Color::LightMagenta,
+                Color::LightCyan,
+                Color::White,
+            ][i as usize],
+        ),
+        Color::Indexed(i) if i < 232 => {
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            let i = i - 16;
+            (level(i / 36), level(i / 6 % 6), level(i % 6))
+        }
+        Color::Indexed(i) => {
+            let gray = 8 + (i - 232) * 10;
+            (gray, gray, gray)
+        }
+        Color::Reset => (255, 255, 255),
+    }
+}
+
+/// How far the hue of the rainbow has moved at `millis` since the epoch, in
+/// degrees. Whole milliseconds are kept, f32 epoch seconds are too coarse.
+fn rainbow_phase(millis: i64) -> f32 {
+    let period = (360.0 / RAINBOW_SPEED * 1000.0) as i64;
+    millis.rem_euclid(period) as f32 / 1000.0 * RAINBOW_SPEED
+}
+
+/// Converts a hue in degrees, saturation and lightness in 0.0..=1.0 to RGB.
+pub fn hsl_to_rgb(hue: f32, saturation: f32, lightness: f32) -> Color {
+    let hue = hue.rem_euclid(360.0) / 60.0;
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as u8 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    let channel = |v: f32| ((v + m) * 255.0).round() as u8;
+    Color::Rgb(channel(r), channel(g), channel(b))
+}
+
+/// Parses a color, one of:
+/// - a terminal color name like "red" or "lightblue", which follows the
+///   terminal palette, or "reset" for the terminal default
+/// - an X11/CSS color name like "tomato" or "dark slate gray"
+/// - `#rgb` or `#rrggbb`
+/// - `rgb(r, g, b)` with channels in 0..=255
+/// - `hsl(h, s%, l%)` with the hue in degrees, 0..=360
+/// - an index in the 256 color palette, 0..=255
+pub fn parse_color(s: &str) -> Result<Color, String> {
+    let invalid = || format!("Invalid color: {}", s);
+    let name: String = s
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .collect::<String>()
+        .to_lowercase();
+    let color = match name.as_str() {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "darkgray" | "darkgrey" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        name => {
+            if let Some(&(_, rgb)) = NAMED_COLORS.iter().find(|(n, _)| *n == name) {
+                let [_, r, g, b] = rgb.to_be_bytes();
+                return Ok(Color::Rgb(r, g, b));
+            }
+            return parse_color_code(s.trim()).ok_or_else(invalid);
+        }
+    };
+    Ok(color)
+}
+
+fn parse_color_code(s: &str) -> Option<Color> {
+    let s = s.to_lowercase();
+    if let Some(hex) = s.strip_prefix('#') {
+        let channel = |i: usize, len: usize| u8::from_str_radix(hex.get(i..i + len)?, 16).ok();
+        return match hex.len() {
+            3 => Some(Color::Rgb(
+                channel(0, 1)? * 17,
+                channel(1, 1)? * 17,
+                channel(2, 1)? * 17,
+            )),
+            6 => Some(Color::Rgb(channel(0, 2)?, channel(2, 2)?, channel(4, 2)?)),
+            _ => None,
+        };
+    }
+    if let Ok(index) = s.parse::<u8>() {
+        return Some(Color::Indexed(index));
+    }
+
+    let reg = Regex::new(r"^(rgb|hsl)\(\s*([^,\s]+)[,\s]+([^,\s]+)[,\s]+([^,\s)]+)\s*\)$").unwrap();
+    let cap = reg.captures(&s)?;
+    let args = [&cap[2], &cap[3], &cap[4]];
+    if &cap[1] == "rgb" {
+        let [r, g, b] = args.map(|arg| arg.parse::<u8>().ok());
+        Some(Color::Rgb(r?, g?, b?))
+    } else {
+        let number = |arg: &str, suffix: &str| arg.trim_end_matches(suffix).parse::<f32>().ok();
+        let hue = number(args[0], "deg").filter(|v| (0.0..=360.0).contains(v))?;
+        let saturation = number(args[1], "%").filter(|v| (0.0..=100.0).contains(v))?;
+        let lightness = number(args[2], "%").filter(|v| (0.0..=100.0).contains(v))?;
+        Some(hsl_to_rgb(hue, saturation / 100.0, lightness / 100.0))
+    }
+}
+
+/// Parses a `ColorSpec`: "rainbow", a gradient through two or more colors
+/// separated by "..", like `#ff0000..#0000ff`, or a single color, see `parse_color`.
+pub fn parse_color_spec(s: &str) -> Result<ColorSpec, String> {
+    if s.trim().eq_ignore_ascii_case("rainbow") {
+        return Ok(ColorSpec::Rainbow);
+    }
+    if !s.contains("..") {
+        return parse_color(s).map(ColorSpec::Solid);
+    }
+    let stops = s
+        .split("..")
+        .map(|stop| parse_color(stop.trim()))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ColorSpec::Gradient(stops))
+}
+
+/// X11/CSS color names without the terminal color names, which are parsed
+/// as terminal colors.
+const NAMED_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("blanchedalmond", 0xffebcd),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgreen", 0x006400),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("greenyellow", 0xadff2f),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightcoral", 0xf08080),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellowgreen", 0x9acd32),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The parts of `text` as one letter each, `_` for separators.
+    fn parts(text: &str, ends_with_seconds: bool) -> String {
+        TimePart::of_text(text, ends_with_seconds)
+            .into_iter()
+            .map(|part| match part {
+                TimePart::Hours => 'h',
+                TimePart::Minutes => 'm',
+                TimePart::Seconds => 's',
+                TimePart::Separator => '_',
+            })
+            .collect()
+    }
+
+    #[test]
+    fn time_parts() {
+        assert_eq!(parts("12:34:56", true), "hh_mm_ss");
+        assert_eq!(parts("12:34:56.789", true), "hh_mm_ss_sss");
+        assert_eq!(parts("4:59.3", true), "m_ss_s");
+        assert_eq!(parts("12:34", false), "hh_mm");
+        // days are counted as hours
+        assert_eq!(parts("1:02:03:04", true), "h_hh_mm_ss");
+    }
+
+    #[test]
+    fn countdown_time_parts() {
+        assert_eq!(parts("-4:59", true), "_m_ss");
+        assert_eq!(parts("-1:00:00.0", true), "_h_mm_ss_s");
+    }
+
+    #[test]
+    fn separators_are_colored_apart() {
+        let colors = TextColors {
+            separators: Some(ColorSpec::Solid(Color::Gray)),
+            ..TextColors::new(ColorSpec::Solid(Color::Green))
+        };
+        let parts = TimePart::of_text("-1:02.3", true);
+        let colors: Vec<_> = parts.iter().map(|part| colors.get(*part)).collect();
+        let gray = &ColorSpec::Solid(Color::Gray);
+        let green = &ColorSpec::Solid(Color::Green);
+        assert_eq!(colors, [gray, green, gray, green, green, gray, green]);
+    }
+
+    #[test]
+    fn parse_color_codes() {
+        assert_eq!(parse_color("#f80"), Ok(Color::Rgb(255, 136, 0)));
+        assert_eq!(parse_color("#FF8800"), Ok(Color::Rgb(255, 136, 0)));
+        assert_eq!(parse_color("rgb(1, 2, 3)"), Ok(Color::Rgb(1, 2, 3)));
+        assert_eq!(parse_color(" RGB( 1 2 3 ) "), Ok(Color::Rgb(1, 2, 3)));
+        assert_eq!(parse_color("hsl(0, 100%, 50%)"), Ok(Color::Rgb(255, 0, 0)));
+        assert_eq!(
+            parse_color("hsl(120deg 100% 25%)"),
+            Ok(Color::Rgb(0, 128, 0))
+        );
+        assert_eq!(
+            parse_color("hsl(360, 0%, 100%)"),
+            Ok(Color::Rgb(255, 255, 255))
+        );
+        assert_eq!(parse_color("0"), Ok(Color::Indexed(0)));
+        assert_eq!(parse_color("208"), Ok(Color::Indexed(208)));
+        assert_eq!(parse_color("255"), Ok(Color::Indexed(255)));
+    }
+
+    #[test]
+    fn parse_color_names() {
+        assert_eq!(parse_color("red"), Ok(Color::Red));
+        assert_eq!(parse_color("LightBlue"), Ok(Color::LightBlue));
+        assert_eq!(parse_color("light-blue"), Ok(Color::LightBlue));
+        assert_eq!(parse_color("dark_grey"), Ok(Color::DarkGray));
+        assert_eq!(parse_color("reset"), Ok(Color::Reset));
+        assert_eq!(parse_color("tomato"), Ok(Color::Rgb(0xff, 0x63, 0x47)));
+        assert_eq!(parse_color("Tomato"), Ok(Color::Rgb(0xff, 0x63, 0x47)));
+        assert_eq!(
+            parse_color("dark slate gray"),
+            Ok(Color::Rgb(0x2f, 0x4f, 0x4f))
+        );
+    }
+
+    #[test]
+    fn parse_invalid_colors() {
+        for s in [
+            "",
+            "nosuchcolor",
+            "#ff",
+            "#ff88",
+            "#ggg",
+            "256",
+            "-1",
+            "rgb(300, 0, 0)",
+            "rgb(1, 2)",
+            "rgb(1, 2, 3, 4)",
+            "hsl(400, 50%, 50%)",
+            "hsl(-10, 50%, 50%)",
+            "hsl(0, 150%, 50%)",
+            "hsl(0, 50%, 101%)",
+        ] {
+            assert!(parse_color(s).is_err(), "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn parse_color_specs() {
+        assert_eq!(parse_color_spec("rainbow"), Ok(ColorSpec::Rainbow));
+        assert_eq!(parse_color_spec(" Rainbow "), Ok(ColorSpec::Rainbow));
+        assert_eq!(parse_color_spec("red"), Ok(ColorSpec::Solid(Color::Red)));
+        assert_eq!(
+            parse_color_spec("#ff0000..#0000ff"),
+            Ok(ColorSpec::Gradient(vec![
+                Color::Rgb(255, 0, 0),
+                Color::Rgb(0, 0, 255)
+            ]))
+        );
+        assert_eq!(
+            parse_color_spec("red .. yellow .. 2"),
+            Ok(ColorSpec::Gradient(vec![
+                Color::Red,
+                Color::Yellow,
+                Color::Indexed(2)
+            ]))
+        );
+        assert!(parse_color_spec("red..").is_err());
+        assert!(parse_color_spec("red..nosuchcolor").is_err());
+    }
+
+    #[test]
+    fn gradient_stops() {
+        let now = Local::now();
+        let spec = ColorSpec::Gradient(vec![Color::Rgb(0, 0, 0), Color::Rgb(200, 100, 0)]);
+        assert_eq!(spec.color_at(0.0, now), Color::Rgb(0, 0, 0));
+        assert_eq!(spec.color_at(0.5, now), Color::Rgb(100, 50, 0));
+        assert_eq!(spec.color_at(1.0, now), Color::Rgb(200, 100, 0));
+        assert_eq!(spec.color_at(2.0, now), Color::Rgb(200, 100, 0));
+
+        let spec = ColorSpec::Gradient(vec![
+            Color::Rgb(0, 0, 0),
+            Color::Rgb(200, 0, 0),
+            Color::Rgb(200, 200, 0),
+        ]);
+        assert_eq!(spec.color_at(0.5, now), Color::Rgb(200, 0, 0));
+        assert_eq!(spec.color_at(0.75, now), Color::Rgb(200, 100, 0));
+    }
+
+    #[test]
+    fn rainbow_cycles() {
+        // 2025-01-01 12:00 UTC
+        let millis = 1_735_732_800_000;
+        assert_ne!(rainbow_phase(millis), rainbow_phase(millis + 500));
+        assert_ne!(rainbow_phase(millis + 500), rainbow_phase(millis + 1000));
+        let period = (360.0 / RAINBOW_SPEED * 1000.0) as i64;
+        assert_eq!(rainbow_phase(millis), rainbow_phase(millis + period));
+    }
+}
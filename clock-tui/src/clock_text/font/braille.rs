@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+use crate::clock_text::point::Point;
+
+use super::{Font, CLOCK_CHARS};
+
+/// Width and height of the design grid the strokes below are drawn on.
+const GRID_WIDTH: f32 = 4.0;
+const GRID_HEIGHT: f32 = 6.0;
+
+/// Size of a glyph in braille dots at scale 1.0, that is 4x3 terminal cells.
+const BASE_DOTS_WIDTH: f32 = 8.0;
+const BASE_DOTS_HEIGHT: f32 = 12.0;
+
+/// Draws glyphs as thin outlines on the 2x4 dot grid of braille characters,
+/// so the clock stays readable in small panes.
+pub struct BrailleFont {
+    pub scale: f32,
+    dots_width: u16,
+    dots_height: u16,
+    glyphs: HashMap<char, Vec<Point>>,
+}
+
+impl BrailleFont {
+    pub fn new(scale: f32) -> Self {
+        let scale = scale.max(1.0);
+        let mut font = Self {
+            scale,
+            dots_width: (BASE_DOTS_WIDTH * scale).round() as u16,
+            dots_height: (BASE_DOTS_HEIGHT * scale).round() as u16,
+            glyphs: HashMap::new(),
+        };
+        for c in CLOCK_CHARS.chars() {
+            let mut points = font.rasterize(Self::get_char_strokes(c));
+            if !c.is_ascii_digit() {
+                // Move narrow glyphs to the first cell so they take less space
+                let left = points.iter().map(|p| p.0 / 2 * 2).min().unwrap_or(0);
+                points.iter_mut().for_each(|p| p.0 -= left);
+            }
+            font.glyphs.insert(c, points);
+        }
+        font
+    }
+
+    /// Every stroke is a polyline on a 4x6 grid, a single point draws a dot.
+    fn get_char_strokes(c: char) -> &'static [&'static [(u8, u8)]] {
+        match c {
+            '0' => &[&[
+                (1, 0),
+                (3, 0),
+                (4, 1),
+                (4, 5),
+                (3, 6),
+                (1, 6),
+                (0, 5),
+                (0, 1),
+                (1, 0),
+            ]],
+            '1' => &[&[(1, 1), (2, 0), (2, 6)], &[(1, 6), (3, 6)]],
+            '2' => &[&[(0, 1), (1, 0), (3, 0), (4, 1), (4, 2), (0, 6), (4, 6)]],
+            '3' => &[
+                &[
+                    (0, 1),
+                    (1, 0),
+                    (3, 0),
+                    (4, 1),
+                    (4, 2),
+                    (3, 3),
+                    (4, 4),
+                    (4, 5),
+                    (3, 6),
+                    (1, 6),
+                    (0, 5),
+                ],
+                &[(1, 3), (3, 3)],
+            ],
+            '4' => &[&[(3, 6), (3, 0), (0, 4), (4, 4)]],
+            '5' => &[&[
+                (4, 0),
+                (0, 0),
+                (0, 3),
+                (3, 3),
+                (4, 4),
+                (4, 5),
+                (3, 6),
+                (0, 6),
+            ]],
+            '6' => &[&[
+                (3, 0),
+                (1, 0),
+                (0, 1),
+                (0, 5),
+                (1, 6),
+                (3, 6),
+                (4, 5),
+                (4, 4),
+                (3, 3),
+                (0, 3),
+            ]],
+            '7' => &[&[(0, 0), (4, 0), (1, 6)]],
+            '8' => &[
+                &[
+                    (1, 0),
+                    (3, 0),
+                    (4, 1),
+                    (4, 2),
+                    (3, 3),
+                    (1, 3),
+                    (0, 2),
+                    (0, 1),
+                    (1, 0),
+                ],
+                &[
+                    (1, 3),
+                    (0, 4),
+                    (0, 5),
+                    (1, 6),
+                    (3, 6),
+                    (4, 5),
+                    (4, 4),
+                    (3, 3),
+                ],
+            ],
+            '9' => &[&[
+                (4, 3),
+                (1, 3),
+                (0, 2),
+                (0, 1),
+                (1, 0),
+                (3, 0),
+                (4, 1),
+                (4, 5),
+                (3, 6),
+                (1, 6),
+            ]],
+            ':' => &[&[(2, 2)], &[(2, 4)]],
+            '.' => &[&[(2, 6)]],
+            '-' => &[&[(0, 3), (4, 3)]],
+            _ => &[],
+        }
+    }
+
+    /// Scales the strokes to the glyph size and plots them with Bresenham's
+    /// algorithm, using a pen as thick as the integer part of the scale.
+    fn rasterize(&self, strokes: &[&[(u8, u8)]]) -> Vec<Point> {
+        let pen = (self.scale.floor() as u16).max(1);
+        let max_x = self.dots_width.saturating_sub(pen) as f32;
+        let max_y = self.dots_height.saturating_sub(pen) as f32;
+        let to_dots = |&(x, y): &(u8, u8)| {
+            (
+                (x as f32 * max_x / GRID_WIDTH).round() as i32,
+                (y as f32 * max_y / GRID_HEIGHT).round() as i32,
+            )
+        };
+
+        let mut points = Vec::new();
+        let mut plot = |x: i32, y: i32| {
+            for dx in 0..pen {
+                for dy in 0..pen {
+                    points.push(Point(x as u16 + dx, y as u16 + dy));
+                }
+            }
+        };
+        for stroke in strokes {
+            let mut from = to_dots(&stroke[0]);
+            plot(from.0, from.1);
+            for to in stroke[1..].iter().map(to_dots) {
+                let (dx, dy) = ((to.0 - from.0).abs(), -(to.1 - from.1).abs());
+                let (sx, sy) = ((to.0 - from.0).signum(), (to.1 - from.1).signum());
+                let mut err = dx + dy;
+                let (mut x, mut y) = from;
+                while (x, y) != to {
+                    let e2 = 2 * err;
+                    if e2 >= dy {
+                        err += dy;
+                        x += sx;
+                    }
+                    if e2 <= dx {
+                        err += dx;
+                        y += sy;
+                    }
+                    plot(x, y);
+                }
+                from = to;
+            }
+        }
+
+        points.sort_by_key(|p| (p.1, p.0));
+        points.dedup();
+        points
+    }
+}
+
+impl Font for BrailleFont {
+    fn get_char(&self, c: char) -> Option<&[Point]> {
+        self.glyphs.get(&c).map(|points| points.as_slice())
+    }
+
+    fn get_char_width(&self) -> u16 {
+        self.dots_width.div_ceil(2)
+    }
+
+    fn get_char_height(&self) -> u16 {
+        self.dots_height.div_ceil(4)
+    }
+
+    fn get_char_advance(&self, c: char) -> u16 {
+        match self.glyphs.get(&c) {
+            Some(points) if !c.is_ascii_digit() => {
+                points.iter().map(|p| p.0 / 2 + 1).max().unwrap_or(0)
+            }
+            _ => self.get_char_width(),
+        }
+    }
+
+    fn get_dots_per_cell(&self) -> (u16, u16) {
+        (2, 4)
+    }
+}
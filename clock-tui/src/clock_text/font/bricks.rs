@@ -1,17 +1,60 @@
+use std::{borrow::Cow, collections::HashMap};
 
 use ratatui::{buffer::Buffer, style::Style};
 
 use crate::clock_text::point::Point;
 
-use super::Font;
+use super::{Font, CLOCK_CHARS};
+
+/// Run-length encoded rows of each glyph, see `BricksFont::draw_row`.
+pub type BricksGlyphs = HashMap<char, Vec<Vec<u16>>>;
 
 pub struct BricksFont {
     pub size: u16,
+    glyphs: BricksGlyphs,
+    width: u16,
+    height: u16,
 }
 
 impl BricksFont {
     pub fn new(size: u16) -> Self {
-        Self { size }
+        Self::with_glyphs(size, HashMap::new())
+    }
+
+    /// Creates a font from user defined glyphs, which may be of any width and
+    /// height. Characters missing from `glyphs` use the built-in ones.
+    pub fn with_glyphs(size: u16, glyphs: BricksGlyphs) -> Self {
+        let mut font = Self {
+            size,
+            glyphs,
+            width: 0,
+            height: 0,
+        };
+        let (width, height) = CLOCK_CHARS.chars().fold((0, 0), |(width, height), c| {
+            let rows = font.get_rows(c);
+            (
+                width.max(Self::rows_width(&rows)),
+                height.max(rows.len() as u16),
+            )
+        });
+        font.width = width;
+        font.height = height;
+        font
+    }
+
+    /// Borrows user defined glyphs, only built-in ones are built on the fly.
+    pub(crate) fn get_rows(&self, c: char) -> Cow<'_, [Vec<u16>]> {
+        match self.glyphs.get(&c) {
+            Some(rows) => Cow::Borrowed(rows),
+            None => Cow::Owned(Vec::from(Self::get_char_matrix_or_placeholder(c))),
+        }
+    }
+
+    pub(crate) fn rows_width(rows: &[Vec<u16>]) -> u16 {
+        rows.iter()
+            .map(|row| row.iter().sum::<u16>())
+            .max()
+            .unwrap_or(0)
     }
 
     /// each row is represented with a vector of numbers:
@@ -21,9 +64,12 @@ impl BricksFont {
     ///   vec![0, 6] is  "██████"
     ///   vec![2, 2] is  "  ██"
     ///   vec![0, 2, 2, 2] is  "██  ██"
+    /// `trim` columns are cut from the leading "off" run.
+    #[allow(clippy::too_many_arguments)]
     fn draw_row(
         start: Point,
-        row: Vec<u16>,
+        row: &[u16],
+        trim: u16,
         size: u16,
         style: Style,
         area_right: u16,
@@ -32,17 +78,21 @@ impl BricksFont {
     ) {
         let mut p = start;
         let mut on = false;
-        for len in row {
-            let len = len * size;
-            if p.0 > area_right {
+        for (i, len) in row.iter().enumerate() {
+            let len = if i == 0 {
+                len.saturating_sub(trim)
+            } else {
+                *len
+            } * size;
+            if p.0 >= area_right {
                 break;
             }
 
             if on {
-                let s = std::cmp::min(len, area_right - p.0 + 1);
+                let s = std::cmp::min(len, area_right - p.0);
                 let line = "█".repeat(s as usize);
                 for r in 0..size {
-                    if p.1 > area_bottom {
+                    if p.1 + r >= area_bottom {
                         break;
                     }
                     buf.set_string(p.0, p.1 + r, line.as_str(), style);
@@ -54,8 +104,76 @@ impl BricksFont {
         }
     }
 
-    fn get_char_matrix(c: char) -> Option<[Vec<u16>; 5]> {
-        match c {
+    /// Width of a glyph trimmed to its bricks, blank glyphs keep their full width.
+    pub(crate) fn glyph_advance(rows: &[Vec<u16>]) -> u16 {
+        match Self::ink_bounds(rows) {
+            (left, right) if right > left => right - left,
+            _ => Self::rows_width(rows),
+        }
+    }
+
+    /// Returns the first column with a brick and the column after the last one.
+    pub(crate) fn ink_bounds(rows: &[Vec<u16>]) -> (u16, u16) {
+        let mut bounds: Option<(u16, u16)> = None;
+        for row in rows {
+            let pixels = Self::row_pixels(row);
+            if let (Some(first), Some(last)) = (
+                pixels.iter().position(|p| *p),
+                pixels.iter().rposition(|p| *p),
+            ) {
+                let (left, right) = bounds.unwrap_or((u16::MAX, 0));
+                bounds = Some((left.min(first as u16), right.max(last as u16 + 1)));
+            }
+        }
+        bounds.unwrap_or((0, 0))
+    }
+
+    /// Encodes one flag per pixel into a run-length encoded row, the reverse of `row_pixels`.
+    pub(crate) fn encode_row(pixels: &[bool]) -> Vec<u16> {
+        let mut row = Vec::new();
+        let mut on = false;
+        let mut len = 0;
+        for &pixel in pixels {
+            if pixel != on {
+                row.push(len);
+                on = pixel;
+                len = 0;
+            }
+            len += 1;
+        }
+        if on {
+            row.push(len);
+        }
+        row
+    }
+
+    /// Expands a run-length encoded row (see `draw_row`) into one flag per pixel.
+    pub(crate) fn row_pixels(row: &[u16]) -> Vec<bool> {
+        let mut pixels = Vec::new();
+        let mut on = false;
+        for len in row {
+            pixels.extend(std::iter::repeat_n(on, *len as usize));
+            on = !on;
+        }
+        pixels
+    }
+
+    /// Like `get_char_matrix`, but a checkerboard stands in for unknown characters.
+    pub(crate) fn get_char_matrix_or_placeholder(c: char) -> [Vec<u16>; 5] {
+        Self::get_char_matrix(c).unwrap_or_else(|| {
+            [
+                vec![0, 1, 1, 1, 1, 1],
+                vec![1, 1, 1, 1, 1, 1],
+                vec![0, 1, 1, 1, 1, 1],
+                vec![1, 1, 1, 1, 1, 1],
+                vec![0, 1, 1, 1, 1, 1],
+            ]
+        })
+    }
+
+    /// Letters are case insensitive.
+    pub(crate) fn get_char_matrix(c: char) -> Option<[Vec<u16>; 5]> {
+        match c.to_ascii_uppercase() {
             '0' => Some([
                 vec![0, 6],
                 vec![0, 2, 2, 2],
@@ -99,6 +217,157 @@ impl BricksFont {
             ':' => Some([vec![], vec![2, 2], vec![], vec![2, 2], vec![]]),
             '.' => Some([vec![], vec![], vec![], vec![], vec![2, 2]]),
             '-' => Some([vec![], vec![], vec![0, 6], vec![], vec![]]),
+            'A' => Some([
+                vec![0, 6],
+                vec![0, 2, 2, 2],
+                vec![0, 6],
+                vec![0, 2, 2, 2],
+                vec![0, 2, 2, 2],
+            ]),
+            'B' => Some([
+                vec![0, 5],
+                vec![0, 2, 2, 2],
+                vec![0, 5],
+                vec![0, 2, 2, 2],
+                vec![0, 5],
+            ]),
+            'C' => Some([vec![0, 6], vec![0, 2], vec![0, 2], vec![0, 2], vec![0, 6]]),
+            'D' => Some([
+                vec![0, 5],
+                vec![0, 2, 2, 2],
+                vec![0, 2, 2, 2],
+                vec![0, 2, 2, 2],
+                vec![0, 5],
+            ]),
+            'E' => Some([vec![0, 6], vec![0, 2], vec![0, 5], vec![0, 2], vec![0, 6]]),
+            'F' => Some([vec![0, 6], vec![0, 2], vec![0, 5], vec![0, 2], vec![0, 2]]),
+            'G' => Some([
+                vec![0, 6],
+                vec![0, 2],
+                vec![0, 2, 1, 3],
+                vec![0, 2, 2, 2],
+                vec![0, 6],
+            ]),
+            'H' => Some([
+                vec![0, 2, 2, 2],
+                vec![0, 2, 2, 2],
+                vec![0, 6],
+                vec![0, 2, 2, 2],
+                vec![0, 2, 2, 2],
+            ]),
+            'I' => Some([vec![0, 6], vec![2, 2], vec![2, 2], vec![2, 2], vec![0, 6]]),
+            'J' => Some([
+                vec![4, 2],
+                vec![4, 2],
+                vec![4, 2],
+                vec![0, 2, 2, 2],
+                vec![0, 6],
+            ]),
+            'K' => Some([
+                vec![0, 2, 2, 2],
+                vec![0, 2, 1, 2],
+                vec![0, 4],
+                vec![0, 2, 1, 2],
+                vec![0, 2, 2, 2],
+            ]),
+            'L' => Some([vec![0, 2], vec![0, 2], vec![0, 2], vec![0, 2], vec![0, 6]]),
+            'M' => Some([
+                vec![0, 6],
+                vec![0, 1, 1, 2, 1, 1],
+                vec![0, 1, 1, 2, 1, 1],
+                vec![0, 1, 4, 1],
+                vec![0, 1, 4, 1],
+            ]),
+            'N' => Some([
+                vec![0, 2, 2, 2],
+                vec![0, 3, 1, 2],
+                vec![0, 6],
+                vec![0, 2, 1, 3],
+                vec![0, 2, 2, 2],
+            ]),
+            'O' => Some([
+                vec![0, 6],
+                vec![0, 2, 2, 2],
+                vec![0, 2, 2, 2],
+                vec![0, 2, 2, 2],
+                vec![0, 6],
+            ]),
+            'P' => Some([
+                vec![0, 6],
+                vec![0, 2, 2, 2],
+                vec![0, 6],
+                vec![0, 2],
+                vec![0, 2],
+            ]),
+            'Q' => Some([
+                vec![0, 6],
+                vec![0, 2, 2, 2],
+                vec![0, 2, 2, 2],
+                vec![0, 2, 1, 3],
+                vec![0, 6],
+            ]),
+            'R' => Some([
+                vec![0, 6],
+                vec![0, 2, 2, 2],
+                vec![0, 5],
+                vec![0, 2, 2, 2],
+                vec![0, 2, 2, 2],
+            ]),
+            'S' => Some([vec![0, 6], vec![0, 2], vec![0, 6], vec![4, 2], vec![0, 6]]),
+            'T' => Some([vec![0, 6], vec![2, 2], vec![2, 2], vec![2, 2], vec![2, 2]]),
+            'U' => Some([
+                vec![0, 2, 2, 2],
+                vec![0, 2, 2, 2],
+                vec![0, 2, 2, 2],
+                vec![0, 2, 2, 2],
+                vec![0, 6],
+            ]),
+            'V' => Some([
+                vec![0, 2, 2, 2],
+                vec![0, 2, 2, 2],
+                vec![0, 2, 2, 2],
+                vec![1, 4],
+                vec![2, 2],
+            ]),
+            'W' => Some([
+                vec![0, 1, 4, 1],
+                vec![0, 1, 4, 1],
+                vec![0, 1, 1, 2, 1, 1],
+                vec![0, 1, 1, 2, 1, 1],
+                vec![0, 6],
+            ]),
+            'X' => Some([
+                vec![0, 2, 2, 2],
+                vec![1, 4],
+                vec![2, 2],
+                vec![1, 4],
+                vec![0, 2, 2, 2],
+            ]),
+            'Y' => Some([
+                vec![0, 2, 2, 2],
+                vec![0, 2, 2, 2],
+                vec![1, 4],
+                vec![2, 2],
+                vec![2, 2],
+            ]),
+            'Z' => Some([vec![0, 6], vec![3, 2], vec![2, 2], vec![1, 2], vec![0, 6]]),
+            '/' => Some([vec![4, 2], vec![3, 2], vec![2, 2], vec![1, 2], vec![0, 2]]),
+            '+' => Some([vec![], vec![2, 2], vec![0, 6], vec![2, 2], vec![]]),
+            '%' => Some([
+                vec![0, 2, 2, 2],
+                vec![3, 2],
+                vec![2, 2],
+                vec![1, 2],
+                vec![0, 2, 2, 2],
+            ]),
+            '=' => Some([vec![], vec![0, 6], vec![], vec![0, 6], vec![]]),
+            '_' => Some([vec![], vec![], vec![], vec![], vec![0, 6]]),
+            ',' => Some([vec![], vec![], vec![], vec![2, 2], vec![1, 2]]),
+            '!' => Some([vec![2, 2], vec![2, 2], vec![2, 2], vec![], vec![2, 2]]),
+            '?' => Some([vec![0, 6], vec![4, 2], vec![2, 4], vec![], vec![2, 2]]),
+            '(' => Some([vec![2, 2], vec![0, 2], vec![0, 2], vec![0, 2], vec![2, 2]]),
+            ')' => Some([vec![0, 2], vec![2, 2], vec![2, 2], vec![2, 2], vec![0, 2]]),
+            ' ' => Some([vec![2], vec![2], vec![2], vec![2], vec![2]]),
             _ => None,
         }
     }
@@ -110,22 +379,76 @@ impl Font for BricksFont {
     }
 
     fn get_char_width(&self) -> u16 {
-        6 * self.size
+        self.width * self.size
     }
 
     fn get_char_height(&self) -> u16 {
-        5 * self.size
+        self.height * self.size
+    }
+
+    fn get_char_advance(&self, c: char) -> u16 {
+        if c.is_ascii_digit() {
+            self.get_char_width()
+        } else {
+            Self::glyph_advance(&self.get_rows(c)) * self.size
+        }
     }
 
     fn draw_char(&self, c: char, x: u16, y: u16, style: Style, buf: &mut Buffer) {
-        if let Some(matrix) = Self::get_char_matrix(c) {
-            let mut start = Point(x, y);
-            let area_right = buf.area.right();
-            let area_bottom = buf.area.bottom();
-            for row in matrix {
-                Self::draw_row(start, row, self.size, style, area_right, area_bottom, buf);
-                start.1 += self.size;
-            }
+        let matrix = self.get_rows(c);
+        // Center narrow digits in the cell, other glyphs are trimmed to their advance
+        let (offset, trim) = if c.is_ascii_digit() {
+            (self.width.saturating_sub(Self::rows_width(&matrix)) / 2, 0)
+        } else {
+            (0, Self::ink_bounds(&matrix).0)
+        };
+        let mut start = Point(x + offset * self.size, y);
+        let area_right = buf.area.right();
+        let area_bottom = buf.area.bottom();
+        for row in matrix.iter() {
+            Self::draw_row(
+                start,
+                row,
+                trim,
+                self.size,
+                style,
+                area_right,
+                area_bottom,
+                buf,
+            );
+            start.1 += self.size;
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ratatui::layout::Rect;
+
+    use super::*;
+
+    fn lines(buf: &Buffer) -> Vec<String> {
+        buf.content
+            .chunks(buf.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn clip_at_the_right_edge() {
+        // The stem of "1" starts right at the edge of the buffer
+        let mut buf = Buffer::empty(Rect::new(0, 0, 2, 5));
+        BricksFont::new(1).draw_char('1', 0, 0, Style::default(), &mut buf);
+        assert_eq!(lines(&buf), vec!["██", "  ", "  ", "  ", "██"]);
+    }
+
+    #[test]
+    fn trim_user_glyphs() {
+        let glyphs = HashMap::from([('-', vec![vec![], vec![2, 3], vec![3, 1]])]);
+        let font = BricksFont::with_glyphs(1, glyphs);
+        assert_eq!(font.get_char_advance('-'), 3);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 3, 3));
+        font.draw_char('-', 0, 0, Style::default(), &mut buf);
+        assert_eq!(lines(&buf), vec!["   ", "███", " █ "]);
+    }
+}
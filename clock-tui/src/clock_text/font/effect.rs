@@ -0,0 +1,202 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+};
+
+use crate::clock_text::point::Point;
+
+use super::{drawn_style, Font};
+
+/// Depth of the `Effect::Extrude` extrusion in cells.
+const EXTRUDE_DEPTH: u16 = 2;
+
+/// Decoration drawn around the glyphs of any font, see `EffectFont`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    /// A drop shadow one cell down and right, in a darker color.
+    Shadow,
+    /// Only the outline of the glyphs, drawn with box-drawing characters.
+    Outline,
+    /// A pseudo-3D extrusion going down and right, darker as it recedes.
+    Extrude,
+}
+
+impl Effect {
+    /// Wraps `font` so its glyphs are drawn with this effect.
+    pub fn apply(self, font: Box<dyn Font>) -> Box<dyn Font> {
+        Box::new(EffectFont::new(font, self))
+    }
+
+    /// Extra columns and rows a glyph takes with this effect.
+    fn get_extra_size(self) -> u16 {
+        match self {
+            Effect::Shadow => 1,
+            Effect::Outline => 2,
+            Effect::Extrude => EXTRUDE_DEPTH,
+        }
+    }
+}
+
+/// A decorator adding an `Effect` to the glyphs of the wrapped font.
+pub struct EffectFont {
+    inner: Box<dyn Font>,
+    effect: Effect,
+}
+
+impl EffectFont {
+    pub fn new(inner: Box<dyn Font>, effect: Effect) -> Self {
+        Self { inner, effect }
+    }
+
+    /// Draws `c` with the wrapped font into a buffer of its own.
+    fn draw_glyph(&self, c: char, style: Style) -> Buffer {
+        let area = Rect::new(
+            0,
+            0,
+            self.inner.get_char_advance(c),
+            self.inner.get_char_height(),
+        );
+        let mut glyph = Buffer::empty(area);
+        self.inner.draw_char(c, 0, 0, style, &mut glyph);
+        glyph
+    }
+}
+
+/// Copies the drawn cells of `glyph` to `(x, y)`, restyled with `style` if given.
+fn copy_glyph(glyph: &Buffer, x: u16, y: u16, style: Option<Style>, buf: &mut Buffer) {
+    for (i, cell) in glyph.content.iter().enumerate() {
+        let (dx, dy) = glyph.pos_of(i);
+        if cell.symbol() == " " || x + dx >= buf.area.right() || y + dy >= buf.area.bottom() {
+            continue;
+        }
+        buf.get_mut(x + dx, y + dy)
+            .set_symbol(cell.symbol())
+            .set_style(style.unwrap_or_else(|| drawn_style(cell)));
+    }
+}
+
+/// Draws a line around the drawn cells of `glyph`, in the cells just outside
+/// of them. `(x, y)` is the top left of the outline, one cell up and left of
+/// the glyph.
+fn draw_outline(glyph: &Buffer, x: u16, y: u16, style: Style, buf: &mut Buffer) {
+    let (width, height) = (glyph.area.width as i32, glyph.area.height as i32);
+    let is_on = |gx: i32, gy: i32| {
+        (0..width).contains(&gx)
+            && (0..height).contains(&gy)
+            && glyph.get(gx as u16, gy as u16).symbol() != " "
+    };
+    let is_line = |gx: i32, gy: i32| {
+        !is_on(gx, gy) && (-1..=1).any(|dx| (-1..=1).any(|dy| is_on(gx + dx, gy + dy)))
+    };
+
+    for gy in -1..=height {
+        for gx in -1..=width {
+            if !is_line(gx, gy) {
+                continue;
+            }
+            // Join neighbouring line cells running along the same glyph edge
+            let up = is_line(gx, gy - 1)
+                && (is_on(gx - 1, gy - 1)
+                    || is_on(gx - 1, gy)
+                    || is_on(gx + 1, gy - 1)
+                    || is_on(gx + 1, gy));
+            let down = is_line(gx, gy + 1)
+                && (is_on(gx - 1, gy)
+                    || is_on(gx - 1, gy + 1)
+                    || is_on(gx + 1, gy)
+                    || is_on(gx + 1, gy + 1));
+            let left = is_line(gx - 1, gy)
+                && (is_on(gx - 1, gy - 1)
+                    || is_on(gx, gy - 1)
+                    || is_on(gx - 1, gy + 1)
+                    || is_on(gx, gy + 1));
+            let right = is_line(gx + 1, gy)
+                && (is_on(gx, gy - 1)
+                    || is_on(gx + 1, gy - 1)
+                    || is_on(gx, gy + 1)
+                    || is_on(gx + 1, gy + 1));
+            let symbol = match (up, down, left, right) {
+                (true, true, false, false) => "│",
+                (false, false, true, true) => "─",
+                (false, true, false, true) => "┌",
+                (false, true, true, false) => "┐",
+                (true, false, false, true) => "└",
+                (true, false, true, false) => "┘",
+                (true, true, false, true) => "├",
+                (true, true, true, false) => "┤",
+                (false, true, true, true) => "┬",
+                (true, false, true, true) => "┴",
+                (true, true, true, true) => "┼",
+                (true, false, false, false) => "╵",
+                (false, true, false, false) => "╷",
+                (false, false, true, false) => "╴",
+                (false, false, false, true) => "╶",
+                (false, false, false, false) => "·",
+            };
+            let (cell_x, cell_y) = (x + (gx + 1) as u16, y + (gy + 1) as u16);
+            if cell_x < buf.area.right() && cell_y < buf.area.bottom() {
+                buf.get_mut(cell_x, cell_y)
+                    .set_symbol(symbol)
+                    .set_style(style);
+            }
+        }
+    }
+}
+
+/// A darker variant of `color`, for shadows and extrusions.
+fn darken(color: Option<Color>) -> Color {
+    match color {
+        Some(Color::Rgb(r, g, b)) => Color::Rgb(r / 5 * 2, g / 5 * 2, b / 5 * 2),
+        Some(Color::LightRed) => Color::Red,
+        Some(Color::LightGreen) => Color::Green,
+        Some(Color::LightYellow) => Color::Yellow,
+        Some(Color::LightBlue) => Color::Blue,
+        Some(Color::LightMagenta) => Color::Magenta,
+        Some(Color::LightCyan) => Color::Cyan,
+        Some(Color::White) => Color::Gray,
+        Some(Color::DarkGray) | Some(Color::Black) => Color::Black,
+        _ => Color::DarkGray,
+    }
+}
+
+impl Font for EffectFont {
+    fn get_char(&self, _c: char) -> Option<&[Point]> {
+        None // Glyphs are drawn by the wrapped font
+    }
+
+    fn get_char_width(&self) -> u16 {
+        self.inner.get_char_width() + self.effect.get_extra_size()
+    }
+
+    fn get_char_height(&self) -> u16 {
+        self.inner.get_char_height() + self.effect.get_extra_size()
+    }
+
+    fn get_char_advance(&self, c: char) -> u16 {
+        self.inner.get_char_advance(c) + self.effect.get_extra_size()
+    }
+
+    fn get_spacing(&self) -> u16 {
+        self.inner.get_spacing()
+    }
+
+    fn draw_char(&self, c: char, x: u16, y: u16, style: Style, buf: &mut Buffer) {
+        let glyph = self.draw_glyph(c, style);
+        match self.effect {
+            Effect::Shadow => {
+                let shadow = style.fg(darken(style.fg));
+                copy_glyph(&glyph, x + 1, y + 1, Some(shadow), buf);
+                copy_glyph(&glyph, x, y, None, buf);
+            }
+            Effect::Outline => draw_outline(&glyph, x, y, style, buf),
+            Effect::Extrude => {
+                for depth in (1..=EXTRUDE_DEPTH).rev() {
+                    let color = (1..depth).fold(darken(style.fg), |c, _| darken(Some(c)));
+                    copy_glyph(&glyph, x + depth, y + depth, Some(style.fg(color)), buf);
+                }
+                copy_glyph(&glyph, x, y, None, buf);
+            }
+        }
+    }
+}
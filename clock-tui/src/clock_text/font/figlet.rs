@@ -0,0 +1,291 @@
+use std::{collections::HashMap, path::Path};
+
+use ratatui::{buffer::Buffer, style::Style};
+
+use crate::clock_text::point::Point;
+
+use super::{bricks::BricksFont, Font, CLOCK_CHARS};
+
+/// Codes of the Deutsch characters that follow the ASCII block in every `.flf` file.
+const DEUTSCH_CODES: [u32; 7] = [196, 214, 220, 228, 246, 252, 223];
+
+/// A font in the FIGlet `.flf` format. Glyphs are kerned, that is blank columns
+/// on both sides are dropped, unless the font asks for full width layout.
+/// Characters the font lacks are drawn with `BricksFont` instead.
+#[derive(Debug, Clone)]
+pub struct FigletFont {
+    glyphs: HashMap<char, Vec<String>>,
+    width: u16,
+    height: u16,
+    fallback_size: u16,
+}
+
+impl FigletFont {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::parse(&content).map_err(|e| format!("Invalid FIGlet font {}: {}", path.display(), e))
+    }
+
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut lines = content.lines();
+        let header = lines.next().ok_or("missing header")?;
+        let signature = header
+            .strip_prefix("flf2a")
+            .ok_or("missing flf2a signature")?;
+        let hardblank = signature.chars().next().ok_or("missing hardblank")?;
+        let params: Vec<i64> = signature[hardblank.len_utf8()..]
+            .split_whitespace()
+            .map(|p| p.parse::<i64>())
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("invalid header: {}", e))?;
+        if params.len() < 5 {
+            return Err("incomplete header".to_string());
+        }
+        let height = params[0].max(1) as usize;
+        let kerning = params[3] >= 0;
+        let comment_lines = params[4].max(0) as usize;
+
+        let mut lines = lines.skip(comment_lines);
+
+        let mut glyphs = HashMap::new();
+        let codes = (32..=126).chain(DEUTSCH_CODES);
+        for code in codes {
+            let Some(rows) = read_glyph(&mut lines, height, hardblank) else {
+                break;
+            };
+            if let Some(c) = char::from_u32(code) {
+                glyphs.insert(c, rows);
+            }
+        }
+        // Code tagged characters, each preceded by a line like "0x2764 HEART"
+        while let Some(tag) = lines.next() {
+            let Some(rows) = read_glyph(&mut lines, height, hardblank) else {
+                break;
+            };
+            let code = tag.split_whitespace().next().and_then(parse_code);
+            if let Some(c) = code.and_then(char::from_u32) {
+                glyphs.insert(c, rows);
+            }
+        }
+
+        let glyphs: HashMap<char, Vec<String>> = glyphs
+            .into_iter()
+            .map(|(c, rows)| (c, if kerning { kern(rows) } else { rows }))
+            .filter(|(c, rows)| *c == ' ' || rows.iter().any(|r| !r.trim().is_empty()))
+            .collect();
+
+        let fallback_size = (height as u16 / 5).max(1);
+        let fallback = BricksFont::new(fallback_size);
+        let width = CLOCK_CHARS
+            .chars()
+            .map(|c| match glyphs.get(&c) {
+                Some(rows) => glyph_width(rows),
+                None => fallback.get_char_width(),
+            })
+            .max()
+            .unwrap_or(0);
+        let height = if CLOCK_CHARS.chars().all(|c| glyphs.contains_key(&c)) {
+            height as u16
+        } else {
+            (height as u16).max(fallback.get_char_height())
+        };
+
+        Ok(Self {
+            glyphs,
+            width,
+            height,
+            fallback_size,
+        })
+    }
+}
+
+fn read_glyph<'a>(
+    lines: &mut impl Iterator<Item = &'a str>,
+    height: usize,
+    hardblank: char,
+) -> Option<Vec<String>> {
+    let rows: Vec<String> = lines
+        .take(height)
+        .map(|line| {
+            let line = line.trim_end();
+            let endmark = line.chars().last().unwrap_or(' ');
+            line.trim_end_matches(endmark).replace(hardblank, " ")
+        })
+        .collect();
+    (rows.len() == height).then_some(rows)
+}
+
+fn parse_code(s: &str) -> Option<u32> {
+    let code = if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        u32::from_str_radix(hex, 16)
+    } else if s.len() > 1 && s.starts_with('0') {
+        u32::from_str_radix(&s[1..], 8)
+    } else {
+        s.parse()
+    };
+    code.ok()
+}
+
+fn glyph_width(rows: &[String]) -> u16 {
+    rows.iter().map(|r| r.chars().count()).max().unwrap_or(0) as u16
+}
+
+/// Drops the blank columns shared by all rows on the left and the right.
+fn kern(rows: Vec<String>) -> Vec<String> {
+    let leading = rows
+        .iter()
+        .filter(|r| !r.trim().is_empty())
+        .map(|r| r.chars().take_while(|c| *c == ' ').count())
+        .min()
+        .unwrap_or(0);
+    rows.into_iter()
+        .map(|r| {
+            r.chars()
+                .skip(leading)
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        })
+        .collect()
+}
+
+impl Font for FigletFont {
+    fn get_char(&self, _c: char) -> Option<&[Point]> {
+        None // FIGlet glyphs are drawn as text rows
+    }
+
+    fn get_char_width(&self) -> u16 {
+        self.width
+    }
+
+    fn get_char_height(&self) -> u16 {
+        self.height
+    }
+
+    fn get_char_advance(&self, c: char) -> u16 {
+        if c.is_ascii_digit() {
+            return self.width;
+        }
+        match self.glyphs.get(&c) {
+            Some(rows) => glyph_width(rows),
+            None => BricksFont::new(self.fallback_size).get_char_advance(c),
+        }
+    }
+
+    fn draw_char(&self, c: char, x: u16, y: u16, style: Style, buf: &mut Buffer) {
+        let Some(rows) = self.glyphs.get(&c) else {
+            let fallback = BricksFont::new(self.fallback_size);
+            let x = x + (self.get_char_advance(c) - fallback.get_char_advance(c)) / 2;
+            fallback.draw_char(c, x, y, style, buf);
+            return;
+        };
+
+        // Center narrow digits in the cell
+        let x = x + (self.get_char_advance(c) - glyph_width(rows)) / 2;
+        for (dy, row) in rows.iter().enumerate() {
+            let cell_y = y + dy as u16;
+            if cell_y >= buf.area.bottom() {
+                break;
+            }
+            for (dx, ch) in row.chars().enumerate() {
+                let cell_x = x + dx as u16;
+                if cell_x >= buf.area.right() {
+                    break;
+                }
+                if ch != ' ' {
+                    buf.get_mut(cell_x, cell_y).set_char(ch).set_style(style);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A font of height 2 with the ASCII and Deutsch blocks drawn by `glyph`,
+    /// blank by default.
+    fn flf(layout: i32, glyph: impl Fn(char) -> Option<[&'static str; 2]>) -> String {
+        let mut content = format!("flf2a$ 2 1 4 {} 1\nA tiny test font\n", layout);
+        for c in (32..=126).chain(DEUTSCH_CODES).filter_map(char::from_u32) {
+            let [top, bottom] = glyph(c).unwrap_or(["$@", "$@@"]);
+            content.push_str(&format!("{}\n{}\n", top, bottom));
+        }
+        content
+    }
+
+    fn digits(c: char) -> Option<[&'static str; 2]> {
+        match c {
+            '1' => Some(["  1$1  @", "  11   @@"]),
+            '0' | '2'..='9' | ':' | '.' | '-' => Some(["#@", "#@@"]),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn parse_header_and_comments() {
+        let font = FigletFont::parse(&flf(0, digits)).unwrap();
+        assert_eq!(font.get_char_height(), 2);
+        assert_eq!(font.glyphs.get(&'2').unwrap(), &vec!["#", "#"]);
+        // blank glyphs are left out, except the space
+        assert!(font.glyphs.contains_key(&' '));
+        assert!(!font.glyphs.contains_key(&'A'));
+    }
+
+    #[test]
+    fn hardblanks_and_kerning() {
+        let font = FigletFont::parse(&flf(0, digits)).unwrap();
+        assert_eq!(font.glyphs.get(&'1').unwrap(), &vec!["1 1", "11"]);
+        assert_eq!(font.get_char_width(), 3);
+        assert_eq!(font.get_char_advance('2'), 3);
+
+        // full width layout keeps the blank columns
+        let font = FigletFont::parse(&flf(-1, digits)).unwrap();
+        assert_eq!(font.glyphs.get(&'1').unwrap(), &vec!["  1 1  ", "  11   "]);
+        assert_eq!(font.get_char_width(), 7);
+    }
+
+    #[test]
+    fn code_tagged_characters() {
+        let content = flf(0, digits) + "0x2764 HEART\n<3@\n<3@@\n";
+        let font = FigletFont::parse(&content).unwrap();
+        assert_eq!(font.glyphs.get(&'❤').unwrap(), &vec!["<3", "<3"]);
+    }
+
+    #[test]
+    fn missing_digits_fall_back_to_bricks() {
+        let font = FigletFont::parse(&flf(0, |_| None)).unwrap();
+        let bricks = BricksFont::new(1);
+        assert_eq!(font.get_char_height(), bricks.get_char_height());
+        assert_eq!(font.get_char_advance('1'), bricks.get_char_width());
+    }
+
+    #[test]
+    fn truncated_font_keeps_complete_glyphs() {
+        let content = flf(0, |c| (c == '!').then_some(["!@", "!@@"]));
+        let truncated: String = content
+            .lines()
+            .take(2 + 2 * 2 + 1)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let font = FigletFont::parse(&truncated).unwrap();
+        assert_eq!(font.glyphs.get(&'!').unwrap(), &vec!["!", "!"]);
+        assert!(!font.glyphs.contains_key(&'"'));
+    }
+
+    #[test]
+    fn malformed_headers() {
+        for content in [
+            "",
+            "flf2 2 1 4 0 1",
+            "tlf2a$ 2 1 4 0 1",
+            "flf2a",
+            "flf2a$ 2 1 4",
+            "flf2a$ 2 one 4 0 1",
+        ] {
+            assert!(FigletFont::parse(content).is_err(), "{:?}", content);
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use ratatui::{buffer::Buffer, style::Style};
+
+use crate::clock_text::point::Point;
+
+use super::{
+    bricks::{BricksFont, BricksGlyphs},
+    Font,
+};
+
+/// Renders the bricks glyphs with `▀`/`▄`/`█`, packing two glyph rows into one
+/// terminal row. Since terminal cells are about twice as tall as wide, this
+/// gives square pixels and lets the clock shrink in steps of half a glyph.
+/// Fractional sizes scale the glyphs to the nearest pixel.
+pub struct HalfBlockFont {
+    pub size: f32,
+    /// The glyphs at one pixel per brick
+    bricks: BricksFont,
+}
+
+impl HalfBlockFont {
+    pub fn new(size: f32) -> Self {
+        Self::with_glyphs(size, HashMap::new())
+    }
+
+    /// Renders user defined glyphs instead, see `BricksFont::with_glyphs`.
+    pub fn with_glyphs(size: f32, glyphs: BricksGlyphs) -> Self {
+        Self {
+            size: size.max(1.0),
+            bricks: BricksFont::with_glyphs(1, glyphs),
+        }
+    }
+
+    /// `len` glyph pixels scaled to the size, in cells or half rows.
+    fn scaled(&self, len: u16) -> u16 {
+        (len as f32 * self.size).round() as u16
+    }
+
+    /// Glyph rows, as half rows of the terminal.
+    fn half_rows(&self) -> u16 {
+        self.scaled(self.bricks.get_char_height())
+    }
+}
+
+impl Font for HalfBlockFont {
+    fn get_char(&self, _c: char) -> Option<&[Point]> {
+        None // Glyphs are the ones of BricksFont
+    }
+
+    fn get_char_width(&self) -> u16 {
+        self.scaled(self.bricks.get_char_width())
+    }
+
+    fn get_char_height(&self) -> u16 {
+        self.half_rows().div_ceil(2)
+    }
+
+    fn get_char_advance(&self, c: char) -> u16 {
+        self.scaled(self.bricks.get_char_advance(c))
+    }
+
+    fn draw_char(&self, c: char, x: u16, y: u16, style: Style, buf: &mut Buffer) {
+        let matrix = self.bricks.get_rows(c);
+        // Center narrow digits in the cell, other glyphs are trimmed to their advance
+        let (offset, trim) = if c.is_ascii_digit() {
+            let width = self.bricks.get_char_width();
+            (width.saturating_sub(BricksFont::rows_width(&matrix)) / 2, 0)
+        } else {
+            (0, BricksFont::ink_bounds(&matrix).0)
+        };
+        let pixels: Vec<Vec<bool>> = matrix.iter().map(|r| BricksFont::row_pixels(r)).collect();
+        let is_on = |col: u16, half_row: u16| {
+            if half_row >= self.half_rows() {
+                return false;
+            }
+            let pixel = |cell: u16| (cell as f32 / self.size) as usize;
+            let Some(col) = (pixel(col) + trim as usize).checked_sub(offset as usize) else {
+                return false;
+            };
+            pixels
+                .get(pixel(half_row))
+                .and_then(|row| row.get(col))
+                .copied()
+                .unwrap_or(false)
+        };
+
+        for row in 0..self.get_char_height() {
+            let cell_y = y + row;
+            if cell_y >= buf.area.bottom() {
+                break;
+            }
+            for col in 0..self.get_char_advance(c) {
+                let cell_x = x + col;
+                if cell_x >= buf.area.right() {
+                    break;
+                }
+                let symbol = match (is_on(col, row * 2), is_on(col, row * 2 + 1)) {
+                    (true, true) => "█",
+                    (true, false) => "▀",
+                    (false, true) => "▄",
+                    (false, false) => continue,
+                };
+                buf.get_mut(cell_x, cell_y)
+                    .set_symbol(symbol)
+                    .set_style(style);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::layout::Rect;
+
+    use super::*;
+
+    fn draw(font: &HalfBlockFont, c: char) -> Vec<String> {
+        let area = Rect::new(0, 0, font.get_char_advance(c), font.get_char_height());
+        let mut buf = Buffer::empty(area);
+        font.draw_char(c, 0, 0, Style::default(), &mut buf);
+        buf.content
+            .chunks(area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn draw_builtin_glyphs() {
+        let font = HalfBlockFont::new(1.0);
+        assert_eq!((font.get_char_width(), font.get_char_height()), (6, 3));
+        assert_eq!(draw(&font, '7'), vec!["▀▀▀▀██", "    ██", "    ▀▀"]);
+    }
+
+    #[test]
+    fn draw_user_glyphs() {
+        let glyphs = HashMap::from([('-', vec![vec![1, 2], vec![2, 1]])]);
+        let font = HalfBlockFont::with_glyphs(1.0, glyphs);
+        assert_eq!(draw(&font, '-'), vec!["▀█", "  ", "  "]);
+    }
+}
@@ -1,21 +1,99 @@
+pub mod braille;
 pub mod bricks;
+pub mod effect;
+pub mod figlet;
+pub mod half_block;
 
-use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+use ratatui::{
+    buffer::{Buffer, Cell},
+    layout::Rect,
+    style::{Color, Style},
+};
 
+use self::{
+    braille::BrailleFont,
+    bricks::{BricksFont, BricksGlyphs},
+    figlet::FigletFont,
+    half_block::HalfBlockFont,
+};
 use super::point::Point;
 
+/// The characters a clock can display, fonts size their glyph cell to fit them.
+pub(crate) const CLOCK_CHARS: &str = "0123456789:.-";
+
+/// The fonts that can be selected with `--font`.
+#[derive(Debug, Clone)]
+pub enum FontKind {
+    /// Full `█` blocks, 5 rows tall at size 1.
+    Bricks,
+    /// `▀`/`▄` half blocks, 3 rows tall at size 1.
+    HalfBlock,
+    /// Braille dot outlines, 3 rows tall at size 1, scales by any factor.
+    Braille,
+    /// A FIGlet font loaded from a `.flf` file, its size is fixed.
+    Figlet(FigletFont),
+    /// A bricks font with user defined glyphs loaded from a `.toml` file.
+    UserBricks(BricksGlyphs),
+    /// User defined bricks glyphs drawn with half blocks, see `HalfBlock`.
+    UserHalfBlock(BricksGlyphs),
+}
+
+impl FontKind {
+    /// Builds the font, bricks fonts round `size` to the nearest integer.
+    pub fn build(&self, size: f32) -> Box<dyn Font> {
+        let block_size = (size.round() as u16).max(1);
+        match self {
+            FontKind::Bricks => Box::new(BricksFont::new(block_size)),
+            FontKind::HalfBlock => Box::new(HalfBlockFont::new(size)),
+            FontKind::Braille => Box::new(BrailleFont::new(size)),
+            FontKind::Figlet(font) => Box::new(font.clone()),
+            FontKind::UserBricks(glyphs) => {
+                Box::new(BricksFont::with_glyphs(block_size, glyphs.clone()))
+            }
+            FontKind::UserHalfBlock(glyphs) => {
+                Box::new(HalfBlockFont::with_glyphs(size, glyphs.clone()))
+            }
+        }
+    }
+}
+
 pub trait Font {
     fn get_char(&self, c: char) -> Option<&[Point]>;
     fn get_char_width(&self) -> u16;
     fn get_char_height(&self) -> u16;
 
+    /// Horizontal space taken by `c`. Digits should all take `get_char_width`
+    /// so the clock doesn't shift as they change, while narrow glyphs like `:`
+    /// and `.` can take less.
+    fn get_char_advance(&self, _c: char) -> u16 {
+        self.get_char_width()
+    }
+
+    /// Blank columns between two glyphs.
+    fn get_spacing(&self) -> u16 {
+        2
+    }
+
+    /// How many points of `get_char` share one terminal cell horizontally and
+    /// vertically. Only `(1, 1)` and the braille grid `(2, 4)` are supported.
+    fn get_dots_per_cell(&self) -> (u16, u16) {
+        (1, 1)
+    }
+
     fn draw_char(&self, c: char, x: u16, y: u16, style: Style, buf: &mut Buffer) {
         if let Some(points) = self.get_char(c) {
+            let (dots_x, dots_y) = self.get_dots_per_cell();
             for point in points {
-                let x = x + point.0;
-                let y = y + point.1;
+                let x = x + point.0 / dots_x;
+                let y = y + point.1 / dots_y;
                 if x < buf.area.right() && y < buf.area.bottom() {
-                    buf.get_mut(x, y).set_style(style);
+                    let cell = buf.get_mut(x, y);
+                    let symbol = if (dots_x, dots_y) == (2, 4) {
+                        add_braille_dot(cell.symbol(), point.0 % 2, point.1 % 4)
+                    } else {
+                        "█".to_string()
+                    };
+                    cell.set_symbol(&symbol).set_style(style);
                 }
             }
         }
@@ -24,13 +102,38 @@ pub trait Font {
     fn draw_str(&self, s: &str, area: Rect, style: Style, buf: &mut Buffer) {
         let mut x = area.x;
         let y = area.y;
-        let spacing = 2;
         for c in s.chars() {
-            if x + self.get_char_width() > area.right() {
+            let advance = self.get_char_advance(c);
+            if x + advance > area.right() {
                 break;
             }
             self.draw_char(c, x, y, style, buf);
-            x += self.get_char_width() + spacing;
+            x += advance + self.get_spacing();
         }
     }
 }
+
+/// Style of a glyph cell drawn into a scratch buffer, leaving out the blank
+/// background of the scratch buffer so the copy keeps the one below.
+pub(crate) fn drawn_style(cell: &Cell) -> Style {
+    let mut style = cell.style();
+    if cell.bg == Color::Reset {
+        style.bg = None;
+    }
+    style
+}
+
+/// Sets the dot at `(x, y)` of the braille pattern in `symbol`, starting from a
+/// blank pattern if the cell does not hold one yet.
+fn add_braille_dot(symbol: &str, x: u16, y: u16) -> String {
+    const BLANK: u32 = 0x2800;
+    const DOTS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+    let pattern = symbol
+        .chars()
+        .next()
+        .map(|c| c as u32)
+        .filter(|c| (BLANK..=BLANK + 0xff).contains(c))
+        .unwrap_or(BLANK);
+    let dot = DOTS[x as usize][y as usize];
+    char::from_u32(pattern | dot).unwrap_or(' ').to_string()
+}
@@ -0,0 +1,175 @@
+use std::{
+    cell::{Cell, RefCell},
+    time::{Duration, Instant},
+};
+
+use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+
+use super::font::{drawn_style, Font};
+
+/// How long a changed glyph takes to turn into the new one.
+const DURATION: Duration = Duration::from_millis(300);
+
+/// Animation played on glyphs that change between frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transition {
+    /// Split-flap display, the top half flips down over the old glyph.
+    Flip,
+    /// The new glyph rolls in from the top, pushing the old one down.
+    Roll,
+    /// The new glyph appears cell by cell in a scattered order.
+    Dissolve,
+}
+
+struct CharState {
+    current: char,
+    previous: char,
+    changed_at: Option<Instant>,
+}
+
+/// Remembers the text drawn in the previous frame, so that `ClockText` can
+/// animate the glyphs that changed. Keep one per widget across frames.
+pub struct TransitionState {
+    pub kind: Transition,
+    chars: RefCell<Vec<CharState>>,
+    now: Cell<Option<Instant>>,
+}
+
+impl TransitionState {
+    pub fn new(kind: Transition) -> Self {
+        Self {
+            kind,
+            chars: RefCell::new(Vec::new()),
+            now: Cell::new(None),
+        }
+    }
+
+    /// Records `text` as drawn at `now`, starting a transition for every char
+    /// that differs from the last frame. A text of different length is drawn
+    /// without transition.
+    pub(crate) fn update(&self, text: &str, now: Instant) {
+        self.now.set(Some(now));
+        let mut chars = self.chars.borrow_mut();
+        if chars.len() != text.chars().count() {
+            *chars = text
+                .chars()
+                .map(|c| CharState {
+                    current: c,
+                    previous: c,
+                    changed_at: None,
+                })
+                .collect();
+            return;
+        }
+        for (state, c) in chars.iter_mut().zip(text.chars()) {
+            if state.current != c {
+                state.previous = state.current;
+                state.current = c;
+                state.changed_at = Some(now);
+            }
+        }
+    }
+
+    /// Returns the char being replaced at `idx` and how far the transition got,
+    /// from 0.0 to 1.0, or `None` if the char at `idx` is not animating.
+    pub(crate) fn get_progress(&self, idx: usize) -> Option<(char, f32)> {
+        let now = self.now.get()?;
+        let chars = self.chars.borrow();
+        let state = chars.get(idx)?;
+        let elapsed = now.saturating_duration_since(state.changed_at?);
+        (elapsed < DURATION).then(|| {
+            (
+                state.previous,
+                elapsed.as_secs_f32() / DURATION.as_secs_f32(),
+            )
+        })
+    }
+
+    /// Returns true while any glyph is still animating.
+    pub fn is_animating(&self) -> bool {
+        (0..self.chars.borrow().len()).any(|idx| self.get_progress(idx).is_some())
+    }
+
+    /// Draws `from` turning into `to` at the given progress, occupying the
+    /// same cells `Font::draw_char` would for `to`.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn draw_char(
+        &self,
+        font: &dyn Font,
+        from: char,
+        to: char,
+        progress: f32,
+        x: u16,
+        y: u16,
+        style: Style,
+        buf: &mut Buffer,
+    ) {
+        let width = font.get_char_advance(from).max(font.get_char_advance(to));
+        let height = font.get_char_height();
+        let scratch = Rect::new(0, 0, width, height);
+        let mut old = Buffer::empty(scratch);
+        font.draw_char(from, 0, 0, style, &mut old);
+        let mut new = Buffer::empty(scratch);
+        font.draw_char(to, 0, 0, style, &mut new);
+
+        let mid = height / 2;
+        let rows = |n: f32| (n.round() as u16).min(height);
+        for row in 0..height {
+            for col in 0..font.get_char_advance(to) {
+                let (source, source_row) = match self.kind {
+                    Transition::Roll => {
+                        let offset = rows(progress * height as f32);
+                        if row < offset {
+                            (&new, height - offset + row)
+                        } else {
+                            (&old, row - offset)
+                        }
+                    }
+                    Transition::Flip if progress < 0.5 => {
+                        // The old top half folds towards the middle, uncovering the new one
+                        let flap = rows((1.0 - 2.0 * progress) * mid as f32);
+                        if row >= mid {
+                            (&old, row)
+                        } else if row >= mid - flap {
+                            (&old, (row + flap - mid) * mid / flap)
+                        } else {
+                            (&new, row)
+                        }
+                    }
+                    Transition::Flip => {
+                        // The new bottom half unfolds from the middle over the old one
+                        let flap = rows((2.0 * progress - 1.0) * (height - mid) as f32);
+                        if row < mid {
+                            (&new, row)
+                        } else if row < mid + flap {
+                            (&new, mid + (row - mid) * (height - mid) / flap)
+                        } else {
+                            (&old, row)
+                        }
+                    }
+                    Transition::Dissolve => {
+                        let threshold = scatter(col, row);
+                        (if threshold < progress { &new } else { &old }, row)
+                    }
+                };
+
+                let cell = source.get(col, source_row.min(height.saturating_sub(1)));
+                if cell.symbol() != " " && x + col < buf.area.right() && y + row < buf.area.bottom()
+                {
+                    buf.get_mut(x + col, y + row)
+                        .set_symbol(cell.symbol())
+                        .set_style(drawn_style(cell));
+                }
+            }
+        }
+    }
+}
+
+/// A fixed pseudo-random value in `0.0..1.0` for each cell.
+fn scatter(col: u16, row: u16) -> f32 {
+    let mut h = (col as u32).wrapping_mul(0x9e37_79b9) ^ (row as u32).wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x2c1b_3c6d);
+    h ^= h >> 12;
+    (h % 1000) as f32 / 1000.0
+}
@@ -1,6 +1,13 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
 use chrono_tz::Tz;
+use ratatui::style::{Color, Modifier, Style};
 use serde::{Deserialize, Deserializer};
 
+use crate::clock_text::color::{parse_color, parse_color_spec, ColorSpec};
+use crate::clock_text::font::bricks::{BricksFont, BricksGlyphs};
+
 fn deserialize_timezone<'de, D>(deserializer: D) -> Result<Option<Tz>, D::Error>
 where
     D: Deserializer<'de>,
@@ -12,6 +19,34 @@ where
     }
 }
 
+fn deserialize_optional_color<'de, D>(deserializer: D) -> Result<Option<Color>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s: Option<String> = Option::deserialize(deserializer)?;
+    s.map(|s| parse_color(&s))
+        .transpose()
+        .map_err(serde::de::Error::custom)
+}
+
+fn deserialize_color_spec<'de, D>(deserializer: D) -> Result<ColorSpec, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    parse_color_spec(&s).map_err(serde::de::Error::custom)
+}
+
+fn deserialize_optional_color_spec<'de, D>(deserializer: D) -> Result<Option<ColorSpec>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s: Option<String> = Option::deserialize(deserializer)?;
+    s.map(|s| parse_color_spec(&s))
+        .transpose()
+        .map_err(serde::de::Error::custom)
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Config {
     #[serde(default)]
@@ -24,16 +59,26 @@ pub struct Config {
     pub stopwatch: StopwatchConfig,
     #[serde(default)]
     pub countdown: CountdownConfig,
+    #[serde(default)]
+    pub colors: ColorsConfig,
+    #[serde(default)]
+    pub theme: ThemeConfig,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct DefaultConfig {
     #[serde(default = "default_mode")]
     pub mode: String,
-    #[serde(default = "default_color")]
-    pub color: String,
+    #[serde(default, deserialize_with = "deserialize_optional_color_spec")]
+    pub color: Option<ColorSpec>,
     #[serde(default = "default_size")]
-    pub size: u16,
+    pub size: f32,
+    #[serde(default = "default_font")]
+    pub font: String,
+    #[serde(default)]
+    pub effect: Option<String>,
+    #[serde(default)]
+    pub transition: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -64,12 +109,24 @@ pub struct TimerConfig {
     pub auto_quit: bool,
     #[serde(default)]
     pub execute: Vec<String>,
+    #[serde(default)]
+    pub thresholds: Vec<ThresholdConfig>,
+    #[serde(default)]
+    pub show_progress: bool,
+    #[serde(default)]
+    pub show_total_progress: bool,
+    #[serde(default)]
+    pub show_segments: bool,
+    #[serde(default)]
+    pub show_end_time: bool,
+    #[serde(default)]
+    pub end_time_format: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct StopwatchConfig {}
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct CountdownConfig {
     #[serde(default)]
     pub time: Option<String>,
@@ -81,14 +138,116 @@ pub struct CountdownConfig {
     pub continue_on_zero: bool,
     #[serde(default)]
     pub reverse: bool,
+    #[serde(default)]
+    pub thresholds: Vec<ThresholdConfig>,
+    #[serde(default)]
+    pub show_progress: bool,
+    #[serde(default)]
+    pub from: Option<String>,
+}
+
+/// Color of the digits once the remaining time is at most `remaining`, eg. "1m".
+#[derive(Debug, Deserialize)]
+pub struct ThresholdConfig {
+    pub remaining: String,
+    #[serde(deserialize_with = "deserialize_color_spec")]
+    pub color: ColorSpec,
+    /// Alternate between `color` and the normal colors every half second
+    #[serde(default)]
+    pub blink: bool,
+}
+
+/// Colors of the parts of the clock, overriding `default.color`.
+#[derive(Debug, Default, Deserialize)]
+pub struct ColorsConfig {
+    #[serde(default, deserialize_with = "deserialize_optional_color_spec")]
+    pub hours: Option<ColorSpec>,
+    #[serde(default, deserialize_with = "deserialize_optional_color_spec")]
+    pub minutes: Option<ColorSpec>,
+    #[serde(default, deserialize_with = "deserialize_optional_color_spec")]
+    pub seconds: Option<ColorSpec>,
+    #[serde(default, deserialize_with = "deserialize_optional_color_spec")]
+    pub separators: Option<ColorSpec>,
+}
+
+/// Overrides of the theme named by `name`, or of the default theme.
+/// User theme files hold the same fields.
+#[derive(Debug, Default, Deserialize)]
+pub struct ThemeConfig {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_color_spec")]
+    pub foreground: Option<ColorSpec>,
+    #[serde(default, deserialize_with = "deserialize_optional_color")]
+    pub background: Option<Color>,
+    #[serde(default)]
+    pub header: Option<StyleConfig>,
+    #[serde(default)]
+    pub footer: Option<StyleConfig>,
+    #[serde(default)]
+    pub paused: Option<StyleConfig>,
+    #[serde(default)]
+    pub warning: Option<StyleConfig>,
+    #[serde(default)]
+    pub finished: Option<StyleConfig>,
+    #[serde(default)]
+    pub effect: Option<String>,
+}
+
+impl ThemeConfig {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        toml::from_str(&content).map_err(|e| format!("Invalid theme {}: {}", path.display(), e))
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct StyleConfig {
+    #[serde(default, deserialize_with = "deserialize_optional_color")]
+    pub fg: Option<Color>,
+    #[serde(default, deserialize_with = "deserialize_optional_color")]
+    pub bg: Option<Color>,
+    #[serde(default)]
+    pub bold: bool,
+    #[serde(default)]
+    pub dim: bool,
+    #[serde(default)]
+    pub italic: bool,
+    #[serde(default)]
+    pub underlined: bool,
+}
+
+impl StyleConfig {
+    pub fn to_style(&self) -> Style {
+        let mut style = Style {
+            fg: self.fg,
+            bg: self.bg,
+            ..Default::default()
+        };
+        for (on, modifier) in [
+            (self.bold, Modifier::BOLD),
+            (self.dim, Modifier::DIM),
+            (self.italic, Modifier::ITALIC),
+            (self.underlined, Modifier::UNDERLINED),
+        ] {
+            if on {
+                style = style.add_modifier(modifier);
+            }
+        }
+        style
+    }
 }
 
 impl Default for DefaultConfig {
     fn default() -> Self {
         Self {
             mode: default_mode(),
-            color: default_color(),
+            color: None,
             size: default_size(),
+            font: default_font(),
+            effect: None,
+            transition: None,
         }
     }
 }
@@ -114,38 +273,76 @@ impl Default for TimerConfig {
             start_paused: false,
             auto_quit: false,
             execute: Vec::new(),
+            thresholds: Vec::new(),
+            show_progress: false,
+            show_total_progress: false,
+            show_segments: false,
+            show_end_time: false,
+            end_time_format: None,
         }
     }
 }
 
-impl Default for StopwatchConfig {
-    fn default() -> Self {
-        Self {}
-    }
+/// A user defined bricks font, stored in a `.toml` file.
+#[derive(Debug, Deserialize)]
+pub struct BricksFontConfig {
+    /// Draw the glyphs with half blocks, like the half-block font
+    #[serde(default = "default_false")]
+    pub half_block: bool,
+    pub glyphs: HashMap<char, GlyphConfig>,
 }
 
-impl Default for CountdownConfig {
-    fn default() -> Self {
-        Self {
-            time: None,
-            title: None,
-            show_millis: false,
-            continue_on_zero: false,
-            reverse: false,
+/// The rows of a glyph, rows may differ in length and glyphs in height.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum GlyphConfig {
+    /// ASCII art in a multi-line string, `#` is a brick and anything else is blank
+    Art(String),
+    /// ASCII art with one string per row
+    ArtRows(Vec<String>),
+    /// Run-length encoded rows, alternating the lengths of "off" and "on" runs
+    RunLength(Vec<Vec<u16>>),
+}
+
+impl GlyphConfig {
+    fn to_rows(&self) -> Vec<Vec<u16>> {
+        let encode = |row: &str| {
+            let pixels: Vec<bool> = row.chars().map(|c| c == '#').collect();
+            BricksFont::encode_row(&pixels)
+        };
+        match self {
+            GlyphConfig::Art(art) => art.lines().map(encode).collect(),
+            GlyphConfig::ArtRows(rows) => rows.iter().map(|r| encode(r)).collect(),
+            GlyphConfig::RunLength(rows) => rows.clone(),
         }
     }
 }
 
+impl BricksFontConfig {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        toml::from_str(&content).map_err(|e| format!("Invalid font {}: {}", path.display(), e))
+    }
+
+    pub fn to_glyphs(&self) -> BricksGlyphs {
+        self.glyphs
+            .iter()
+            .map(|(c, glyph)| (*c, glyph.to_rows()))
+            .collect()
+    }
+}
+
 fn default_mode() -> String {
     "clock".to_string()
 }
 
-fn default_color() -> String {
-    "green".to_string()
+fn default_size() -> f32 {
+    1.0
 }
 
-fn default_size() -> u16 {
-    1
+fn default_font() -> String {
+    "bricks".to_string()
 }
 
 fn default_true() -> bool {
@@ -160,25 +357,25 @@ fn default_timer_durations() -> Vec<String> {
     vec!["25m".to_string(), "5m".to_string()]
 }
 
-impl Config {
-    pub fn load() -> Option<Self> {
-        // ~/.config/tclock/config.toml
-        let config_path = dirs::home_dir()?
-            .join(".config")
-            .join("tclock")
-            .join("config.toml");
+/// The directory holding `config.toml` and user fonts, i.e. `~/.config/tclock`.
+pub fn config_dir() -> Option<PathBuf> {
+    Some(dirs::home_dir()?.join(".config").join("tclock"))
+}
 
+impl Config {
+    /// Loads `~/.config/tclock/config.toml`, `None` if there is no such file.
+    pub fn load() -> Result<Option<Self>, String> {
+        let Some(config_path) = config_dir().map(|dir| dir.join("config.toml")) else {
+            return Ok(None);
+        };
         if !config_path.exists() {
-            return None;
+            return Ok(None);
         };
 
-        let content = std::fs::read_to_string(config_path).ok()?;
-        match toml::from_str(&content) {
-            Ok(config) => Some(config),
-            Err(e) => {
-                eprintln!("解析配置文件失败: {}", e);
-                None
-            }
-        }
+        let content = std::fs::read_to_string(&config_path)
+            .map_err(|e| format!("Failed to read {}: {}", config_path.display(), e))?;
+        toml::from_str(&content)
+            .map(Some)
+            .map_err(|e| format!("Invalid config file {}: {}", config_path.display(), e))
     }
 }
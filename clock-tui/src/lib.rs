@@ -1,3 +1,6 @@
 pub mod app;
 pub mod clock_text;
 pub mod config;
+pub mod session;
+pub mod time_source;
+pub mod widgets;
@@ -0,0 +1,203 @@
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::time_source::TimeSource;
+
+/// The widget a session belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionKind {
+    Timer,
+    Stopwatch,
+}
+
+/// State of a timer or stopwatch kept across restarts, see `--session`.
+/// A running one keeps the wall-clock time it was saved at, so the time
+/// tclock was not running counts as well.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionState {
+    pub kind: SessionKind,
+    /// Milliseconds run until it was saved
+    pub passed_millis: i64,
+    /// When it was saved, `None` if it was paused
+    pub started_at: Option<DateTime<Local>>,
+}
+
+/// The directory sessions are saved in, i.e. `~/.local/state/tclock`.
+pub fn state_dir() -> Option<PathBuf> {
+    Some(
+        dirs::home_dir()?
+            .join(".local")
+            .join("state")
+            .join("tclock"),
+    )
+}
+
+fn session_path(name: &str) -> Result<PathBuf, String> {
+    state_dir()
+        .map(|dir| dir.join(format!("{}.toml", name)))
+        .ok_or_else(|| "Failed to find the home directory".to_string())
+}
+
+impl SessionState {
+    /// The state of a widget that ran for `passed` before `started_at`, the
+    /// monotonic time it was last resumed at if it is running.
+    pub(crate) fn new(
+        kind: SessionKind,
+        passed: Duration,
+        started_at: Option<Instant>,
+        time: &dyn TimeSource,
+    ) -> Self {
+        let passed = passed + started_at.map_or(Duration::zero(), |t| time.elapsed(t));
+        Self {
+            kind,
+            passed_millis: passed.num_milliseconds(),
+            started_at: started_at.map(|_| time.now()),
+        }
+    }
+
+    /// The time passed and the monotonic time it was resumed at, the reverse
+    /// of `new`.
+    pub(crate) fn restore(&self, time: &dyn TimeSource) -> (Duration, Option<Instant>) {
+        let mut passed = Duration::milliseconds(self.passed_millis);
+        if let Some(saved_at) = self.started_at {
+            passed += (time.now() - saved_at).max(Duration::zero());
+        }
+        (passed, self.started_at.map(|_| time.instant()))
+    }
+
+    /// Loads the session `name`, `None` if it was never saved.
+    pub fn load(name: &str) -> Result<Option<Self>, String> {
+        Self::load_from(&session_path(name)?)
+    }
+
+    pub fn save(&self, name: &str) -> Result<(), String> {
+        self.save_to(&session_path(name)?)
+    }
+
+    fn load_from(path: &Path) -> Result<Option<Self>, String> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        toml::from_str(&content)
+            .map(Some)
+            .map_err(|e| format!("Invalid session {}: {}", path.display(), e))
+    }
+
+    fn save_to(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let content = toml::to_string(self).map_err(|e| e.to_string())?;
+        // Written aside and renamed over the session, so a kill mid-write
+        // leaves the last session whole
+        let tmp = path.with_extension(format!("toml.{}.tmp", std::process::id()));
+        std::fs::write(&tmp, content)
+            .and_then(|_| std::fs::rename(&tmp, path))
+            .map_err(|e| {
+                let _ = std::fs::remove_file(&tmp);
+                format!("Failed to write {}: {}", path.display(), e)
+            })
+    }
+
+    /// Forgets the session `name`, so the next run starts afresh.
+    pub fn remove(name: &str) -> Result<(), String> {
+        let path = session_path(name)?;
+        if !path.exists() {
+            return Ok(());
+        }
+        std::fs::remove_file(&path)
+            .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration as StdDuration;
+
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::time_source::ManualClock;
+
+    fn round_trip(state: &SessionState, name: &str) -> SessionState {
+        let path = std::env::temp_dir()
+            .join(format!("tclock-test-{}", std::process::id()))
+            .join(format!("{}.toml", name));
+        state.save_to(&path).unwrap();
+        let loaded = SessionState::load_from(&path).unwrap().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        loaded
+    }
+
+    #[test]
+    fn restore_running_counts_time_closed() {
+        let clock = ManualClock::new(Local.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap());
+        let resumed_at = clock.instant();
+        clock.advance(StdDuration::from_secs(7));
+        let state = SessionState::new(
+            SessionKind::Timer,
+            Duration::seconds(5),
+            Some(resumed_at),
+            &clock,
+        );
+        let loaded = round_trip(&state, "running");
+        assert_eq!(loaded.kind, SessionKind::Timer);
+        assert_eq!(loaded.passed_millis, 12_000);
+
+        // run again a minute later
+        let clock = ManualClock::new(Local.with_ymd_and_hms(2025, 1, 1, 12, 1, 7).unwrap());
+        let (passed, started_at) = loaded.restore(&clock);
+        assert_eq!(passed, Duration::seconds(72));
+        assert_eq!(started_at, Some(clock.instant()));
+    }
+
+    #[test]
+    fn restore_paused_ignores_time_closed() {
+        let clock = ManualClock::new(Local.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap());
+        let state = SessionState::new(SessionKind::Stopwatch, Duration::seconds(5), None, &clock);
+        let loaded = round_trip(&state, "paused");
+        assert_eq!(loaded.kind, SessionKind::Stopwatch);
+
+        clock.advance(StdDuration::from_secs(3600));
+        assert_eq!(loaded.restore(&clock), (Duration::seconds(5), None));
+    }
+
+    #[test]
+    fn restore_ignores_the_clock_going_back() {
+        let clock = ManualClock::new(Local.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap());
+        let state = SessionState::new(
+            SessionKind::Timer,
+            Duration::seconds(5),
+            Some(clock.instant()),
+            &clock,
+        );
+        let loaded = round_trip(&state, "back");
+
+        clock.set(Local.with_ymd_and_hms(2025, 1, 1, 11, 0, 0).unwrap());
+        assert_eq!(loaded.restore(&clock).0, Duration::seconds(5));
+    }
+
+    #[test]
+    fn save_replaces_the_session() {
+        let clock = ManualClock::new(Local.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap());
+        let dir = std::env::temp_dir().join(format!("tclock-test-{}-save", std::process::id()));
+        let path = dir.join("replace.toml");
+        let state =
+            |secs| SessionState::new(SessionKind::Timer, Duration::seconds(secs), None, &clock);
+        state(5).save_to(&path).unwrap();
+        state(8).save_to(&path).unwrap();
+
+        let loaded = SessionState::load_from(&path).unwrap().unwrap();
+        assert_eq!(loaded.passed_millis, 8_000);
+        // nothing is left aside
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,85 @@
+use std::cell::Cell;
+use std::time::Instant;
+
+use chrono::{DateTime, Duration, Local};
+
+/// Where the app and the modes get the time from, so elapsed time can be
+/// measured on a monotonic clock and a fake clock can be injected, eg. to
+/// render a mode at a given time with ratatui's `TestBackend`.
+pub trait TimeSource {
+    /// Wall-clock time, for what is displayed and for countdown targets.
+    fn now(&self) -> DateTime<Local>;
+
+    /// Monotonic time, only meaningful relative to another `Instant`. Unlike
+    /// the wall clock it never jumps when the system time is changed.
+    fn instant(&self) -> Instant;
+
+    /// Time passed since `since`, as told by `instant`.
+    fn elapsed(&self, since: Instant) -> Duration {
+        Duration::from_std(self.instant().saturating_duration_since(since)).unwrap_or(Duration::MAX)
+    }
+}
+
+/// The time of the system, optionally shifted to start at another time.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock {
+    offset: Duration,
+}
+
+impl SystemClock {
+    /// A clock telling `now` at this moment and running from there.
+    pub fn starting_at(now: DateTime<Local>) -> Self {
+        Self {
+            offset: now - Local::now(),
+        }
+    }
+}
+
+impl TimeSource for SystemClock {
+    fn now(&self) -> DateTime<Local> {
+        Local::now() + self.offset
+    }
+
+    fn instant(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that only moves when told to, for tests and reproducible renders.
+#[derive(Debug)]
+pub struct ManualClock {
+    now: Cell<DateTime<Local>>,
+    start: Instant,
+    elapsed: Cell<std::time::Duration>,
+}
+
+impl ManualClock {
+    pub fn new(now: DateTime<Local>) -> Self {
+        Self {
+            now: Cell::new(now),
+            start: Instant::now(),
+            elapsed: Cell::new(std::time::Duration::ZERO),
+        }
+    }
+
+    /// Moves both the wall clock and the monotonic clock forward.
+    pub fn advance(&self, duration: std::time::Duration) {
+        self.elapsed.set(self.elapsed.get() + duration);
+        self.now.set(self.now.get() + duration);
+    }
+
+    /// Sets the wall clock only, like a change of the system time would.
+    pub fn set(&self, now: DateTime<Local>) {
+        self.now.set(now);
+    }
+}
+
+impl TimeSource for ManualClock {
+    fn now(&self) -> DateTime<Local> {
+        self.now.get()
+    }
+
+    fn instant(&self) -> Instant {
+        self.start + self.elapsed.get()
+    }
+}
@@ -0,0 +1,22 @@
+//! The clock, timer, stopwatch and countdown of tclock as ratatui widgets, to
+//! embed them in other apps without going through the `App` of the CLI.
+//!
+//! Each widget is made with its builder, eg. `TimerWidget::builder()`. The
+//! clock and the countdown are drawn with `Frame::render_widget`. The timer
+//! and the stopwatch keep the time they have been running in a
+//! [`TimerState`] or [`StopwatchState`], drawn with
+//! `Frame::render_stateful_widget` and paused and resumed through [`Pause`].
+//! All of them can take the time from a [`TimeSource`] other than the system
+//! time, like a [`ManualClock`].
+
+pub use crate::app::modes::{
+    format_duration, ClockWidget, ClockWidgetBuilder, CountdownWidget, CountdownWidgetBuilder,
+    DurationFormat, Pause, RunState, Status, StopwatchState, StopwatchWidget,
+    StopwatchWidgetBuilder, Threshold, TimerState, TimerWidget, TimerWidgetBuilder, MAX_DURATION,
+};
+pub use crate::app::theme::Theme;
+pub use crate::clock_text::color::{ColorSpec, TextColors};
+pub use crate::clock_text::font::effect::Effect;
+pub use crate::clock_text::font::FontKind;
+pub use crate::clock_text::transition::Transition;
+pub use crate::time_source::{ManualClock, SystemClock, TimeSource};
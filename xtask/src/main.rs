@@ -14,7 +14,7 @@ fn build_shell_completion(outdir: &Path) -> Result<()> {
     let shells = Shell::value_variants();
 
     for shell in shells {
-        generate_to(*shell, &mut app, BIN_NAME, &outdir)?;
+        generate_to(*shell, &mut app, BIN_NAME, outdir)?;
     }
 
     Ok(())